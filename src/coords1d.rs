@@ -0,0 +1,515 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Represents a 1D grid with wrapping, that is, a ring of cells.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::WrappingCoords1d;
+/// let w1d = WrappingCoords1d::new(10).unwrap();
+/// assert_eq!(w1d.index(-1), 9);
+/// assert_eq!(w1d.shift(9, 3), 2);
+/// assert_eq!(w1d.neighbors(0, 2), vec![1, 9, 2, 8]);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct WrappingCoords1d {
+    /// Width of the grid; it has to be larger than 0.
+    w32: i32,
+    /// Width of the grid.
+    wu: usize,
+}
+
+impl WrappingCoords1d {
+    /// Constructs a new WrappingCoords1d object.
+    ///
+    /// # Errors
+    ///
+    /// `width` must be larger than 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingCoords1d};
+    /// assert!(WrappingCoords1d::new(10).is_ok());
+    /// assert!(matches!(WrappingCoords1d::new(0), Err(ErrorKind::DimensionsLessThan1)));
+    /// ```
+    pub fn new(width: i32) -> Result<WrappingCoords1d, ErrorKind> {
+        if width > 0 {
+            Ok(WrappingCoords1d {
+                w32: width,
+                wu: width as usize,
+            })
+        } else {
+            Err(ErrorKind::DimensionsLessThan1)
+        }
+    }
+    /// Returns the width of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.width(), 10);
+    /// ```
+    pub fn width(&self) -> i32 {
+        self.w32
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.size(), 10 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.wu
+    }
+    /// Returns the total number of cells in the grid as an `i32` number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.size32(), 10);
+    /// ```
+    pub fn size32(&self) -> i32 {
+        self.w32
+    }
+    /// Returns an index into the grid based on the `x` coordinate. The coordinate wraps around the limits of the grid
+    /// and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.index(5), 5);
+    /// assert_eq!(w1d.index(-11), 9);
+    /// assert_eq!(w1d.index(i32::MIN), 2);
+    /// ```
+    pub fn index(&self, x: i32) -> usize {
+        WrappingCoords2d::modulo(x, self.w32) as usize
+    }
+    /// Returns the `x` coordinate based on an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.coords(9), 9);
+    /// ```
+    pub fn coords(&self, index: usize) -> i32 {
+        index as i32
+    }
+    /// Returns the `x` coordinate based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.coords_checked(9), Some(9));
+    /// assert_eq!(w1d.coords_checked(10), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<i32> {
+        if index < self.wu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns a new index into the grid based on a starting index `start_index` and an offset `delta_x`.
+    /// The offset can be negative and can take any value in the range of `i32`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.shift(0, -1), 9);
+    /// assert_eq!(w1d.shift(9, i32::MAX), 6);
+    /// ```
+    pub fn shift(&self, start_index: usize, delta_x: i32) -> usize {
+        let x = (start_index % self.wu) as i32; // Reduced, so that invalid indices stay in the grid
+        WrappingCoords2d::wrapping_add(x, delta_x, self.w32) as usize
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its neighbors
+    /// within `radius` cells. The indices are ordered by distance, and the right neighbor comes before the left
+    /// neighbor at each distance: `[x + 1, x - 1, x + 2, x - 2, ...]`. This is the order of
+    /// [`WrappingCoords::neighbors_moore`](crate::WrappingCoords::neighbors_moore) for `N = 1`.
+    /// Radii larger than `width` are reduced to `width`, because farther cells only repeat the cells of closer rings.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// assert_eq!(w1d.neighbors(5, 1), vec![6, 4]);
+    /// assert_eq!(w1d.neighbors(9, 3), vec![0, 8, 1, 7, 2, 6]);
+    /// ```
+    pub fn neighbors(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        let r = radius.min(self.w32 as u32) as i32; // Farther cells repeat closer ones
+        (1..=r)
+            .flat_map(|k| [k, -k])
+            .map(|dx| self.shift(start_index, dx))
+            .collect()
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its neighbors within `radius` cells,
+    /// ordered as in [`neighbors`](#method.neighbors).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords1d;
+    /// let w1d = WrappingCoords1d::new(10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w1d.for_each(2, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors, &w1d.neighbors(this_cell_index, 2)[..]);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w1d.size());
+    /// ```
+    pub fn for_each<F>(&self, radius: u32, mut f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        // Farther cells repeat closer ones
+        let r = radius.min(self.w32 as u32) as i32;
+        // Shifts in the form (x + w) only grow, so the loop reduces them without converting from i32
+        let mut x_shifts: std::vec::Vec<usize> = (1..=r)
+            .flat_map(|k| [k, -k])
+            .map(|dx| WrappingCoords2d::modulo(dx, self.w32) as usize + self.wu)
+            .collect();
+        let mut neighbors = vec![0; x_shifts.len()];
+        for this_cell_index in 0..self.wu {
+            for (neighbor, shift) in neighbors.iter_mut().zip(x_shifts.iter_mut()) {
+                *neighbor = *shift % self.wu;
+                *shift += 1;
+            }
+            f(this_cell_index, &neighbors);
+        }
+    }
+}
+
+/// Represents the rule table of a 1D cellular automaton with `states` states and neighborhoods of radius `radius`.
+///
+/// The next state of a cell is `table[i]`, where `i` is the number whose base-`states` digits are the states of the
+/// cells from `x - radius` to `x + radius`, with the leftmost cell as the most significant digit. This is the
+/// convention of Wolfram codes; see [`wolfram`](#method.wolfram).
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{Rule1d, WrappingCoords1d};
+/// let w1d = WrappingCoords1d::new(7).unwrap();
+/// let rule90 = Rule1d::wolfram(90);
+/// let history = rule90.history(&w1d, &[0, 0, 0, 1, 0, 0, 0], 2);
+/// assert_eq!(
+///     history,
+///     vec![
+///         vec![0, 0, 0, 1, 0, 0, 0],
+///         vec![0, 0, 1, 0, 1, 0, 0],
+///         vec![0, 1, 0, 0, 0, 1, 0],
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule1d {
+    /// Number of states; cells hold states in the range [0, states).
+    states: u8,
+    /// Radius of the neighborhood.
+    radius: u32,
+    /// Next state for each neighborhood; its length is states^(2 * radius + 1).
+    table: std::vec::Vec<u8>,
+}
+
+impl Rule1d {
+    /// Constructs a new Rule1d object for an automaton with `states` states and neighborhoods of radius `radius`.
+    /// `table[i]` is the next state of a cell whose neighborhood, read from left to right as base-`states` digits, is `i`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::InvalidRule` if `states` is 0, if the neighborhood is wider than the widest
+    /// ring, that is, if 2 * radius + 1 exceeds `std::i32::MAX`, if the length of `table` differs from
+    /// states^(2 * radius + 1), or if an entry of `table` is not in the range [0, states).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, Rule1d};
+    /// // Rule 110, with the table written from neighborhood 000 to neighborhood 111:
+    /// let rule = Rule1d::new(2, 1, vec![0, 1, 1, 1, 0, 1, 1, 0]).unwrap();
+    /// assert_eq!(rule, Rule1d::wolfram(110));
+    /// assert!(matches!(Rule1d::new(2, 1, vec![0; 4]), Err(ErrorKind::InvalidRule)));
+    /// assert!(matches!(Rule1d::new(2, 0, vec![0, 2]), Err(ErrorKind::InvalidRule)));
+    /// // A 3-state rule with radius 2 needs 3^5 entries:
+    /// assert!(Rule1d::new(3, 2, vec![0; 243]).is_ok());
+    /// // A single state fits any table of length 1, but the neighborhood has to fit in a ring:
+    /// assert!(Rule1d::new(1, 1 << 29, vec![0]).is_ok());
+    /// assert!(matches!(Rule1d::new(1, 1 << 30, vec![0]), Err(ErrorKind::InvalidRule)));
+    /// ```
+    pub fn new(states: u8, radius: u32, table: std::vec::Vec<u8>) -> Result<Rule1d, ErrorKind> {
+        let len = radius
+            .checked_mul(2)
+            .and_then(|d| d.checked_add(1))
+            .filter(|&digits| digits <= i32::MAX as u32) // Widest ring
+            .and_then(|digits| (states as usize).checked_pow(digits));
+        if states == 0 || len != Some(table.len()) || table.iter().any(|&s| s >= states) {
+            Err(ErrorKind::InvalidRule)
+        } else {
+            Ok(Rule1d {
+                states,
+                radius,
+                table,
+            })
+        }
+    }
+    /// Returns the rule of an elementary cellular automaton, with 2 states and radius 1, given its Wolfram code `number`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Rule1d, WrappingCoords1d};
+    /// let w1d = WrappingCoords1d::new(5).unwrap();
+    /// assert_eq!(Rule1d::wolfram(30).step(&w1d, &[0, 0, 1, 0, 0]), vec![0, 1, 1, 1, 0]);
+    /// ```
+    pub fn wolfram(number: u8) -> Rule1d {
+        Rule1d {
+            states: 2,
+            radius: 1,
+            table: (0..8).map(|i| (number >> i) & 1).collect(),
+        }
+    }
+    /// Returns the number of states.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Rule1d;
+    /// assert_eq!(Rule1d::wolfram(30).states(), 2);
+    /// ```
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+    /// Returns the radius of the neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Rule1d;
+    /// assert_eq!(Rule1d::wolfram(30).radius(), 1);
+    /// ```
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+    /// Returns the rule table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Rule1d;
+    /// assert_eq!(Rule1d::wolfram(30).table(), &[0, 1, 1, 1, 1, 0, 0, 0]);
+    /// ```
+    pub fn table(&self) -> &[u8] {
+        &self.table
+    }
+    /// Applies the rule once to every cell of the ring `w1d`, whose states are given by `cells`,
+    /// and returns the next states.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `cells` differs from `w1d.size()`, or if a cell holds a state
+    /// outside of the range [0, states).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Rule1d, WrappingCoords1d};
+    /// let w1d = WrappingCoords1d::new(5).unwrap();
+    /// // Rule 90 wraps around the ring:
+    /// assert_eq!(Rule1d::wolfram(90).step(&w1d, &[1, 0, 0, 0, 0]), vec![0, 1, 0, 0, 1]);
+    /// ```
+    pub fn step(&self, w1d: &WrappingCoords1d, cells: &[u8]) -> std::vec::Vec<u8> {
+        assert_eq!(
+            cells.len(),
+            w1d.size(),
+            "the length of cells differs from the size of the grid"
+        );
+        assert!(
+            cells.iter().all(|&s| s < self.states),
+            "a cell holds a state outside of the range [0, states)"
+        );
+        if self.table.len() == 1 {
+            // A single state has a single neighborhood
+            return vec![self.table[0]; cells.len()];
+        }
+        let k = self.states as usize;
+        // At most (i32::MAX - 1) / 2
+        let r = self.radius as i32;
+        // Weight of the leftmost digit of the neighborhood
+        let leftmost = self.table.len() / k;
+        // Neighborhood of the first cell
+        let mut neighborhood =
+            (-r..=r).fold(0, |acc, dx| acc * k + cells[w1d.shift(0, dx)] as usize);
+        let mut next = std::vec::Vec::with_capacity(cells.len());
+        for this_cell_index in 0..cells.len() {
+            next.push(self.table[neighborhood]);
+            // Slide the neighborhood one cell to the right
+            let outgoing = cells[w1d.shift(this_cell_index, -r)] as usize;
+            let incoming = cells[w1d.shift(this_cell_index, r.wrapping_add(1))] as usize;
+            neighborhood = (neighborhood - outgoing * leftmost) * k + incoming;
+        }
+        next
+    }
+    /// Applies the rule `generations` times, starting from `initial`, and returns the space-time history:
+    /// `initial` followed by the states after each generation. Each row of the history has the length of `initial`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as [`step`](#method.step).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Rule1d, WrappingCoords1d};
+    /// let w1d = WrappingCoords1d::new(31).unwrap();
+    /// let mut initial = vec![0; w1d.size()];
+    /// initial[w1d.index(15)] = 1;
+    /// let history = Rule1d::wolfram(30).history(&w1d, &initial, 15);
+    /// assert_eq!(history.len(), 16);
+    /// // Each generation of rule 30 grows by one cell on each side
+    /// assert_eq!(history[15][0], 1);
+    /// ```
+    pub fn history(
+        &self,
+        w1d: &WrappingCoords1d,
+        initial: &[u8],
+        generations: usize,
+    ) -> std::vec::Vec<std::vec::Vec<u8>> {
+        let mut history = std::vec::Vec::with_capacity(generations + 1);
+        history.push(initial.to_vec());
+        for generation in 0..generations {
+            let next = self.step(w1d, &history[generation]);
+            history.push(next);
+        }
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WrappingCoords;
+
+    #[test]
+    fn rings_and_rules() {
+        // Rings agree with the 1D WrappingCoords
+        for width in 1..8 {
+            let w1d = WrappingCoords1d::new(width).unwrap();
+            let wnd = WrappingCoords::new([width]).unwrap();
+            for index in 0..w1d.size() {
+                let x = w1d.coords(index);
+                assert_eq!(w1d.index(x - 3 * width), index);
+                for &dx in [-9, 1, i32::MIN, i32::MAX].iter() {
+                    assert_eq!(w1d.shift(index, dx), wnd.shift(index, [dx]));
+                }
+                for radius in 0..4 {
+                    assert_eq!(
+                        w1d.neighbors(index, radius),
+                        wnd.neighbors_moore(index, radius)
+                    );
+                }
+            }
+            let mut calls_counter = 0;
+            w1d.for_each(3, |this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w1d.neighbors(this_cell_index, 3)[..]);
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, w1d.size());
+        }
+        // Every elementary rule agrees with a direct evaluation of the Wolfram code
+        let w1d = WrappingCoords1d::new(9).unwrap();
+        let cells = [1, 0, 1, 1, 0, 0, 0, 1, 1];
+        for number in 0..=255 {
+            let next = Rule1d::wolfram(number).step(&w1d, &cells);
+            for (index, &state) in next.iter().enumerate() {
+                let left = cells[w1d.shift(index, -1)];
+                let right = cells[w1d.shift(index, 1)];
+                let i = 4 * left + 2 * cells[index] + right;
+                assert_eq!(state, (number >> i) & 1);
+            }
+        }
+        // Rule 90 draws the Sierpinski triangle
+        let w1d = WrappingCoords1d::new(7).unwrap();
+        let history = Rule1d::wolfram(90).history(&w1d, &[0, 0, 0, 1, 0, 0, 0], 3);
+        assert_eq!(history[3], vec![1, 0, 1, 0, 1, 0, 1]);
+        // A 3-state rule with radius 2 that copies the state of the cell two places to the left
+        let table: std::vec::Vec<u8> = (0..243).map(|i| (i / 81) as u8).collect();
+        let rule = Rule1d::new(3, 2, table).unwrap();
+        let w1d = WrappingCoords1d::new(6).unwrap();
+        assert_eq!(rule.step(&w1d, &[0, 1, 2, 0, 1, 2]), vec![1, 2, 0, 1, 2, 0]);
+        // Neighborhoods wider than the ring wrap onto the same cells
+        let w1d = WrappingCoords1d::new(2).unwrap();
+        assert_eq!(rule.step(&w1d, &[1, 2]), vec![1, 2]);
+        // Radius 0 rules map each state independently
+        let rule = Rule1d::new(3, 0, vec![1, 2, 0]).unwrap();
+        assert_eq!(rule.step(&w1d, &[0, 2]), vec![1, 0]);
+        assert!(matches!(
+            Rule1d::new(0, 0, vec![]),
+            Err(ErrorKind::InvalidRule)
+        ));
+        assert!(matches!(
+            Rule1d::new(2, u32::MAX, vec![]),
+            Err(ErrorKind::InvalidRule)
+        ));
+        // Single-state rules accept any radius up to the widest ring, and step without scanning the neighborhood
+        let rule = Rule1d::new(1, (i32::MAX as u32 - 1) / 2, vec![0]).unwrap();
+        assert_eq!(rule.step(&w1d, &[0, 0]), vec![0, 0]);
+        for &radius in [i32::MAX as u32 / 2 + 1, 1 << 31, u32::MAX].iter() {
+            assert!(matches!(
+                Rule1d::new(1, radius, vec![0]),
+                Err(ErrorKind::InvalidRule)
+            ));
+        }
+        // Invalid start indices wrap around the ring
+        let w1d = WrappingCoords1d::new(10).unwrap();
+        assert_eq!(w1d.shift(25, 0), 5);
+        assert_eq!(w1d.shift(usize::MAX, 1), w1d.shift(usize::MAX % 10, 1));
+    }
+}
@@ -0,0 +1,700 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Offsets `(dx, dy, dz)` of the 6-neighborhood: the 4-neighborhood in the layer of the cell, counter-clockwise,
+/// starting from the right neighbor, followed by the cells above and below.
+const OFFSETS6: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (0, 1, 0),
+    (-1, 0, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// Offsets `(dx, dy, dz)` of the 18-neighborhood: the 8-neighborhood in the layer of the cell, followed by the
+/// cell above and its 4-neighborhood, and the cell below and its 4-neighborhood. Each 2D neighborhood is ordered
+/// counter-clockwise, starting from the right neighbor.
+const OFFSETS18: [(i32, i32, i32); 18] = [
+    (1, 0, 0),
+    (1, 1, 0),
+    (0, 1, 0),
+    (-1, 1, 0),
+    (-1, 0, 0),
+    (-1, -1, 0),
+    (0, -1, 0),
+    (1, -1, 0),
+    (0, 0, 1),
+    (1, 0, 1),
+    (0, 1, 1),
+    (-1, 0, 1),
+    (0, -1, 1),
+    (0, 0, -1),
+    (1, 0, -1),
+    (0, 1, -1),
+    (-1, 0, -1),
+    (0, -1, -1),
+];
+
+/// Offsets `(dx, dy, dz)` of the 26-neighborhood: the 8-neighborhood in the layer of the cell, followed by the
+/// cell above and its 8-neighborhood, and the cell below and its 8-neighborhood. Each 2D neighborhood is ordered
+/// counter-clockwise, starting from the right neighbor.
+const OFFSETS26: [(i32, i32, i32); 26] = [
+    (1, 0, 0),
+    (1, 1, 0),
+    (0, 1, 0),
+    (-1, 1, 0),
+    (-1, 0, 0),
+    (-1, -1, 0),
+    (0, -1, 0),
+    (1, -1, 0),
+    (0, 0, 1),
+    (1, 0, 1),
+    (1, 1, 1),
+    (0, 1, 1),
+    (-1, 1, 1),
+    (-1, 0, 1),
+    (-1, -1, 1),
+    (0, -1, 1),
+    (1, -1, 1),
+    (0, 0, -1),
+    (1, 0, -1),
+    (1, 1, -1),
+    (0, 1, -1),
+    (-1, 1, -1),
+    (-1, 0, -1),
+    (-1, -1, -1),
+    (0, -1, -1),
+    (1, -1, -1),
+];
+
+/// Represents a 3D grid with wrapping, such as a layered landscape or a volumetric world.
+///
+/// Cells are stored layer by layer: the index of the cell at `(x, y, z)` is `(z * height + y) * width + x`.
+/// Each layer is a 2D grid with the same layout as a [`WrappingCoords2d`] grid.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::WrappingCoords3d;
+/// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+/// assert_eq!(w3d.index(1, 2, 3), 321);
+/// assert_eq!(w3d.coords(321), (1, 2, 3));
+/// // The layer below the bottom layer is the top layer:
+/// assert_eq!(w3d.shift(321, 0, 0, -4), 921);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct WrappingCoords3d {
+    /// Width of the grid; it has to be larger than 0.
+    w32: i32,
+    /// Height of the grid; it has to be larger than 0.
+    h32: i32,
+    /// Depth of the grid; it has to be larger than 0.
+    d32: i32,
+    /// Total number of cells in the grid; it has to be larger than 0 and smaller than std::i32::MAX.
+    sz32: i32,
+    /// Width of the grid.
+    wu: usize,
+    /// Number of cells in each layer.
+    whu: usize,
+    /// Total number of cells in the grid.
+    szu: usize,
+}
+
+impl WrappingCoords3d {
+    /// Constructs a new WrappingCoords3d object.
+    ///
+    /// # Errors
+    ///
+    /// `width`, `height`, and `depth` must be larger than 0. Also, their product must be smaller than
+    /// `std::i32::MAX = 2147483647`; see [`WrappingCoords2d::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingCoords3d};
+    /// assert!(WrappingCoords3d::new(10, 10, 10).is_ok());
+    /// assert!(matches!(WrappingCoords3d::new(10, 0, 10), Err(ErrorKind::DimensionsLessThan1)));
+    /// assert!(matches!(WrappingCoords3d::new(2000, 2000, 2000), Err(ErrorKind::DimensionsTooLarge)));
+    /// ```
+    pub fn new(width: i32, height: i32, depth: i32) -> Result<WrappingCoords3d, ErrorKind> {
+        if width > 0 && height > 0 && depth > 0 {
+            match width
+                .checked_mul(height)
+                .and_then(|layer| layer.checked_mul(depth))
+            {
+                Some(s) => Ok(WrappingCoords3d {
+                    w32: width,
+                    h32: height,
+                    d32: depth,
+                    sz32: s,
+                    wu: width as usize,
+                    whu: (width * height) as usize,
+                    szu: s as usize,
+                }),
+                None => Err(ErrorKind::DimensionsTooLarge),
+            }
+        } else {
+            Err(ErrorKind::DimensionsLessThan1)
+        }
+    }
+    /// Returns the width of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 20, 30).unwrap();
+    /// assert_eq!(w3d.width(), 10);
+    /// ```
+    pub fn width(&self) -> i32 {
+        self.w32
+    }
+    /// Returns the height of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 20, 30).unwrap();
+    /// assert_eq!(w3d.height(), 20);
+    /// ```
+    pub fn height(&self) -> i32 {
+        self.h32
+    }
+    /// Returns the depth of the grid, the number of layers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 20, 30).unwrap();
+    /// assert_eq!(w3d.depth(), 30);
+    /// ```
+    pub fn depth(&self) -> i32 {
+        self.d32
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 20, 30).unwrap();
+    /// assert_eq!(w3d.size(), 6000 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.szu
+    }
+    /// Returns the total number of cells in the grid as an `i32` number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 20, 30).unwrap();
+    /// assert_eq!(w3d.size32(), 6000);
+    /// ```
+    pub fn size32(&self) -> i32 {
+        self.sz32
+    }
+    /// Returns an index into the grid based on `x`, `y`, and `z` coordinates. Coordinates wrap around the limits
+    /// of the grid and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.index(5, 9, 0), 95);
+    /// assert_eq!(w3d.index(5, 9, 1), 195);
+    /// assert_eq!(w3d.index(-1, -1, -1), 999);
+    /// assert_eq!(w3d.index(i32::MAX, i32::MIN, i32::MAX), 727);
+    /// ```
+    pub fn index(&self, x: i32, y: i32, z: i32) -> usize {
+        let mx = WrappingCoords2d::modulo(x, self.w32);
+        let my = WrappingCoords2d::modulo(y, self.h32);
+        let mz = WrappingCoords2d::modulo(z, self.d32);
+        ((mz * self.h32 + my) * self.w32 + mx) as usize // Smaller than size; no overflow
+    }
+    /// Returns `x`, `y`, and `z` coordinates based on an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.coords(0), (0, 0, 0));
+    /// assert_eq!(w3d.coords(195), (5, 9, 1));
+    /// assert_eq!(w3d.coords(999), (9, 9, 9));
+    /// ```
+    pub fn coords(&self, index: usize) -> (i32, i32, i32) {
+        let idx32 = index as i32; // Always positive
+        let layer = idx32 / self.w32;
+        (idx32 % self.w32, layer % self.h32, layer / self.h32)
+    }
+    /// Returns `x`, `y`, and `z` coordinates based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.coords_checked(999), Some((9, 9, 9)));
+    /// assert_eq!(w3d.coords_checked(1000), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<(i32, i32, i32)> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, and x, y, and z offsets.
+    /// Offsets can be negative and can take any value in the range of `i32`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.shift(195, 1, 1, 0), 106);
+    /// assert_eq!(w3d.shift(195, 0, 0, 1), 295);
+    /// assert_eq!(w3d.shift(0, -1, -1, -1), 999);
+    /// assert_eq!(w3d.shift(0, i32::MAX, i32::MIN, i32::MAX), 727);
+    /// ```
+    pub fn shift(&self, start_index: usize, delta_x: i32, delta_y: i32, delta_z: i32) -> usize {
+        let (x, y, z) = self.coords(start_index % self.szu); // Reduced, so that invalid indices stay in the grid
+        let new_x = WrappingCoords2d::wrapping_add(x, delta_x, self.w32);
+        let new_y = WrappingCoords2d::wrapping_add(y, delta_y, self.h32);
+        let new_z = WrappingCoords2d::wrapping_add(z, delta_z, self.d32);
+        ((new_z * self.h32 + new_y) * self.w32 + new_x) as usize
+    }
+    /// Returns the indices to the neighbors of the cell given by `start_index`, in the order of `offsets`.
+    fn neighbors_array<const N: usize>(
+        &self,
+        start_index: usize,
+        offsets: &[(i32, i32, i32); N],
+    ) -> [usize; N] {
+        offsets.map(|(dx, dy, dz)| self.shift(start_index, dx, dy, dz))
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 6 neighbors,
+    /// which share a face with the cell. The indices are ordered counter-clockwise in the layer of the cell,
+    /// starting from the neighbor to the right, followed by the cell above (+z) and the cell below (-z).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.neighbors6(195), vec![196, 105, 194, 185, 295, 95]);
+    /// assert_eq!(w3d.neighbors6(0), vec![1, 10, 9, 90, 100, 900]);
+    /// ```
+    pub fn neighbors6(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors6_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 6 neighbors,
+    /// in the same order as [`neighbors6`](#method.neighbors6). Unlike `neighbors6`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.neighbors6_array(0), [1, 10, 9, 90, 100, 900]);
+    /// ```
+    pub fn neighbors6_array(&self, start_index: usize) -> [usize; 6] {
+        self.neighbors_array(start_index, &OFFSETS6)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 18 neighbors,
+    /// which share a face or an edge with the cell. The indices start with the 8-neighborhood in the layer of the cell,
+    /// followed by the cell above (+z) and its 4-neighborhood, and the cell below (-z) and its 4-neighborhood.
+    /// Each 2D neighborhood is ordered counter-clockwise, starting from the neighbor to the right.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(
+    ///     w3d.neighbors18(0),
+    ///     vec![1, 11, 10, 19, 9, 99, 90, 91, 100, 101, 110, 109, 190, 900, 901, 910, 909, 990]
+    /// );
+    /// ```
+    pub fn neighbors18(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors18_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 18 neighbors,
+    /// in the same order as [`neighbors18`](#method.neighbors18). Unlike `neighbors18`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.neighbors18_array(555)[8..13], [655, 656, 665, 654, 645]);
+    /// ```
+    pub fn neighbors18_array(&self, start_index: usize) -> [usize; 18] {
+        self.neighbors_array(start_index, &OFFSETS18)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 26 neighbors,
+    /// which share a face, an edge, or a corner with the cell. The indices start with the 8-neighborhood in the layer
+    /// of the cell, followed by the cell above (+z) and its 8-neighborhood, and the cell below (-z) and its 8-neighborhood.
+    /// Each 2D neighborhood is ordered counter-clockwise, starting from the neighbor to the right.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let neighbors = w3d.neighbors26(555);
+    /// assert_eq!(neighbors[..8], [556, 566, 565, 564, 554, 544, 545, 546]);
+    /// assert_eq!(neighbors[8..17], [655, 656, 666, 665, 664, 654, 644, 645, 646]);
+    /// assert_eq!(neighbors[17..], [455, 456, 466, 465, 464, 454, 444, 445, 446]);
+    /// ```
+    pub fn neighbors26(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors26_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 26 neighbors,
+    /// in the same order as [`neighbors26`](#method.neighbors26). Unlike `neighbors26`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// assert_eq!(w3d.neighbors26_array(0)[17], 900);
+    /// ```
+    pub fn neighbors26_array(&self, start_index: usize) -> [usize; 26] {
+        self.neighbors_array(start_index, &OFFSETS26)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and the neighbors given by `offsets`.
+    /// Like `WrappingCoords2d::for_each`, this function offsets the shifts by the period of each axis, in the form
+    /// (x + w, yw + wh, zwh + sz), so the loop only adds to and reduces usize values, without converting from i32.
+    fn for_each<F, const N: usize>(&self, mut f: F, offsets: &[(i32, i32, i32); N])
+    where
+        F: FnMut(usize, &[usize; N]),
+    {
+        let x_shifts0 =
+            offsets.map(|(dx, _, _)| WrappingCoords2d::modulo(dx, self.w32) as usize + self.wu);
+        let yw_shifts0 = offsets
+            .map(|(_, dy, _)| WrappingCoords2d::modulo(dy, self.h32) as usize * self.wu + self.whu);
+        let mut x_shifts = x_shifts0;
+        let mut yw_shifts = yw_shifts0;
+        let mut zwh_shifts = offsets.map(|(_, _, dz)| {
+            WrappingCoords2d::modulo(dz, self.d32) as usize * self.whu + self.szu
+        });
+        let mut neighbors = [0; N];
+        let mut this_cell_index = 0;
+        loop {
+            for (j, neighbor) in neighbors.iter_mut().enumerate() {
+                *neighbor =
+                    zwh_shifts[j] % self.szu + yw_shifts[j] % self.whu + x_shifts[j] % self.wu;
+            }
+            // Evaluation
+            f(this_cell_index, &neighbors);
+            // Next iteration
+            this_cell_index += 1;
+            if this_cell_index == self.szu {
+                break;
+            }
+            // Locate the neighbors
+            if this_cell_index % self.whu == 0 {
+                x_shifts = x_shifts0;
+                yw_shifts = yw_shifts0;
+                for j in zwh_shifts.iter_mut() {
+                    *j += self.whu;
+                }
+            } else if this_cell_index % self.wu == 0 {
+                x_shifts = x_shifts0;
+                for j in yw_shifts.iter_mut() {
+                    *j += self.wu;
+                }
+            } else {
+                for j in x_shifts.iter_mut() {
+                    *j += 1;
+                }
+            }
+        }
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 6 neighbors,
+    /// ordered as in [`neighbors6`](#method.neighbors6).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each6(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], w3d.shift(this_cell_index, 1, 0, 0));
+    ///     assert_eq!(neighbors[4], w3d.shift(this_cell_index, 0, 0, 1));
+    ///     assert_eq!(neighbors[5], w3d.shift(this_cell_index, 0, 0, -1));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w3d.size());
+    /// ```
+    pub fn for_each6<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 6]),
+    {
+        self.for_each(f, &OFFSETS6)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 18 neighbors,
+    /// ordered as in [`neighbors18`](#method.neighbors18).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each18(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[8], w3d.shift(this_cell_index, 0, 0, 1));
+    ///     assert_eq!(neighbors[17], w3d.shift(this_cell_index, 0, -1, -1));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w3d.size());
+    /// ```
+    pub fn for_each18<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 18]),
+    {
+        self.for_each(f, &OFFSETS18)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 26 neighbors,
+    /// ordered as in [`neighbors26`](#method.neighbors26).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each26(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[8], w3d.shift(this_cell_index, 0, 0, 1));
+    ///     assert_eq!(neighbors[25], w3d.shift(this_cell_index, 1, -1, -1));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w3d.size());
+    /// ```
+    pub fn for_each26<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 26]),
+    {
+        self.for_each(f, &OFFSETS26)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 6 neighbors,
+    /// ordered as in [`neighbors6`](#method.neighbors6).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each_pair6(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index != neighbor_index);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 6 * w3d.size());
+    /// ```
+    pub fn for_each_pair6<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each6(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 18 neighbors,
+    /// ordered as in [`neighbors18`](#method.neighbors18).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each_pair18(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 18 * w3d.size());
+    /// ```
+    pub fn for_each_pair18<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each18(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 26 neighbors,
+    /// ordered as in [`neighbors26`](#method.neighbors26).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords3d;
+    /// let w3d = WrappingCoords3d::new(10, 10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w3d.for_each_pair26(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 26 * w3d.size());
+    /// ```
+    pub fn for_each_pair26<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each26(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids3d() {
+        assert!(matches!(
+            WrappingCoords3d::new(0, 1, 1),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingCoords3d::new(1, 1, -1),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingCoords3d::new(2, 1, i32::MAX),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(WrappingCoords3d::new(1, 1, i32::MAX).is_ok());
+        for &(w, h, d) in [(5, 4, 3), (1, 1, 1), (2, 3, 1), (1, 2, 7), (3, 3, 3)].iter() {
+            let g = WrappingCoords3d::new(w, h, d).unwrap();
+            // Each layer has the layout of a 2D grid
+            let layer = WrappingCoords2d::new(w, h).unwrap();
+            for index in 0..g.size() {
+                let (x, y, z) = g.coords(index);
+                assert_eq!(g.index(x, y, z), index);
+                assert_eq!(g.index(x + w, y - h, z + 2 * d), index);
+                assert_eq!(index % layer.size(), layer.index(x, y));
+                assert_eq!(g.coords_checked(index), Some((x, y, z)));
+                for &(dx, dy, dz) in OFFSETS26.iter() {
+                    assert_eq!(g.shift(index, dx, dy, dz), g.index(x + dx, y + dy, z + dz));
+                }
+                // The in-plane neighbors follow the 2D order
+                let in_plane: std::vec::Vec<usize> = layer
+                    .neighbors8(layer.index(x, y))
+                    .into_iter()
+                    .map(|i| i + index - index % layer.size())
+                    .collect();
+                assert_eq!(g.neighbors26(index)[..8], in_plane[..]);
+                assert_eq!(g.neighbors18(index)[..8], in_plane[..]);
+            }
+            assert_eq!(g.coords_checked(g.size()), None);
+            let mut calls_counter = 0;
+            g.for_each6(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &g.neighbors6_array(this_cell_index));
+                calls_counter += 1;
+            });
+            g.for_each18(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &g.neighbors18_array(this_cell_index));
+                calls_counter += 1;
+            });
+            g.for_each26(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &g.neighbors26_array(this_cell_index));
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, 3 * g.size());
+        }
+        // 18 and 26 neighbors are distinct, and they are the cells that share a face, edge, or corner
+        let g = WrappingCoords3d::new(3, 3, 3).unwrap();
+        for index in 0..g.size() {
+            let mut neighbors = g.neighbors26(index);
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            assert_eq!(neighbors.len(), 26);
+            assert!(!neighbors.contains(&index));
+        }
+        for &(dx, dy, dz) in OFFSETS18.iter() {
+            assert!(dx.abs() + dy.abs() + dz.abs() <= 2);
+        }
+        // Coordinates and offsets can take any value in the range of `i32`
+        let g = WrappingCoords3d::new(i32::MAX, 1, 1).unwrap();
+        assert_eq!(g.shift(0, -1, i32::MAX, i32::MIN), g.size() - 1);
+        assert_eq!(
+            g.neighbors6(g.size() - 1),
+            vec![
+                0,
+                g.size() - 1,
+                g.size() - 2,
+                g.size() - 1,
+                g.size() - 1,
+                g.size() - 1
+            ]
+        );
+        // Invalid start indices wrap around the grid
+        for &start in [g.size(), 3 * g.size() + 7, usize::MAX].iter() {
+            assert_eq!(g.shift(start, 0, 0, 0), start % g.size());
+            assert_eq!(
+                g.shift(start, 1, -1, 1),
+                g.shift(start % g.size(), 1, -1, 1)
+            );
+        }
+    }
+}
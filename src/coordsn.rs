@@ -0,0 +1,549 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Returns the offsets of every cell whose distance to the center lies in the range [1, `radius`], ordered ring by ring,
+/// starting with the closest ring. `manhattan` selects the Manhattan distance (von Neumann neighborhood) instead of the
+/// Chebyshev distance (Moore neighborhood).
+///
+/// In 1D, each ring at distance `k` is `[k, -k]`. In 2D, the offsets follow the order of
+/// `WrappingCoords2d::neighbors_moore` and `WrappingCoords2d::neighbors_von_neumann`. In higher dimensions,
+/// each ring is ordered with the first axis varying fastest, like the indices of the grid.
+fn offsets<const N: usize>(radius: u32, manhattan: bool) -> std::vec::Vec<[i32; N]> {
+    let r = i32::try_from(radius).unwrap_or(i32::MAX); // Offsets have to fit in i32
+    match N {
+        0 => std::vec::Vec::new(),
+        1 => (1..=r)
+            .flat_map(|k| [k, -k])
+            .map(|dx| {
+                let mut offset = [0; N];
+                offset[0] = dx;
+                offset
+            })
+            .collect(),
+        2 => {
+            let planar = if manhattan {
+                crate::von_neumann_offsets(radius)
+            } else {
+                crate::moore_offsets(radius)
+            };
+            planar
+                .into_iter()
+                .map(|(dx, dy)| {
+                    let mut offset = [0; N];
+                    offset[0] = dx;
+                    offset[1] = dy;
+                    offset
+                })
+                .collect()
+        }
+        _ => {
+            let distance = |offset: &[i32; N]| -> i64 {
+                let lengths = offset.iter().map(|&d| (d as i64).abs());
+                if manhattan {
+                    lengths.sum()
+                } else {
+                    lengths.max().unwrap_or(0)
+                }
+            };
+            let mut offsets = std::vec::Vec::new();
+            let mut offset = [-r; N];
+            'cube: loop {
+                let d = distance(&offset);
+                if d > 0 && d <= r as i64 {
+                    offsets.push(offset);
+                }
+                // Next offset in the cube [-r, r]^N, with the first axis varying fastest
+                for d in offset.iter_mut() {
+                    if *d < r {
+                        *d += 1;
+                        continue 'cube;
+                    }
+                    *d = -r;
+                }
+                break;
+            }
+            offsets.sort_by_key(distance); // Stable; keeps the order within each ring
+            offsets
+        }
+    }
+}
+
+/// Represents an N-dimensional grid with wrapping, such as a 1D ring (`N = 1`) or a 4D hypertorus (`N = 4`).
+///
+/// Cells are stored with the first axis varying fastest: the index of the cell at `[x0, x1, x2]` is
+/// `(x2 * d1 + x1) * d0 + x0`, where `[d0, d1, d2]` are the dimensions of the grid. For `N = 2`, this type agrees with
+/// [`WrappingCoords2d`] on every query, including the order of the neighbors; `WrappingCoords2d` remains the
+/// specialized, faster type for 2D grids. For `N = 3`, indices and coordinates agree with
+/// [`WrappingCoords3d`](crate::WrappingCoords3d).
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{WrappingCoords, WrappingCoords2d};
+/// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+/// assert_eq!(w4d.index([1, 2, 3, 4]), 4321);
+/// assert_eq!(w4d.coords(4321), [1, 2, 3, 4]);
+/// assert_eq!(w4d.shift(4321, [-2, 0, 0, 5]), 9329);
+/// // A 2D grid
+/// let wnd = WrappingCoords::new([10, 10]).unwrap();
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// assert_eq!(wnd.neighbors_moore(0, 1), w2d.neighbors8(0));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct WrappingCoords<const N: usize> {
+    /// Dimensions of the grid; each has to be larger than 0.
+    dims: [i32; N],
+    /// Distance between the indices of two cells that are adjacent along each axis.
+    strides: [usize; N],
+    /// Total number of cells in the grid; it has to be larger than 0 and smaller than std::i32::MAX.
+    sz32: i32,
+    /// Total number of cells in the grid.
+    szu: usize,
+}
+
+impl<const N: usize> WrappingCoords<N> {
+    /// Constructs a new WrappingCoords object.
+    ///
+    /// # Errors
+    ///
+    /// `N` and every dimension in `dims` must be larger than 0. Also, the product of the dimensions must be smaller than
+    /// `std::i32::MAX = 2147483647`; see [`WrappingCoords2d::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingCoords};
+    /// assert!(WrappingCoords::new([10, 10, 10, 10]).is_ok());
+    /// assert!(matches!(WrappingCoords::new([10, 0, 10]), Err(ErrorKind::DimensionsLessThan1)));
+    /// assert!(matches!(WrappingCoords::new([]), Err(ErrorKind::DimensionsLessThan1)));
+    /// assert!(matches!(WrappingCoords::new([300; 4]), Err(ErrorKind::DimensionsTooLarge)));
+    /// ```
+    pub fn new(dims: [i32; N]) -> Result<WrappingCoords<N>, ErrorKind> {
+        if N == 0 || dims.iter().any(|&d| d < 1) {
+            return Err(ErrorKind::DimensionsLessThan1);
+        }
+        let mut strides = [0; N];
+        let mut s: i32 = 1;
+        for (stride, &d) in strides.iter_mut().zip(dims.iter()) {
+            *stride = s as usize;
+            s = s.checked_mul(d).ok_or(ErrorKind::DimensionsTooLarge)?;
+        }
+        Ok(WrappingCoords {
+            dims,
+            strides,
+            sz32: s,
+            szu: s as usize,
+        })
+    }
+    /// Returns the dimensions of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 20, 30, 40]).unwrap();
+    /// assert_eq!(w4d.dims(), [10, 20, 30, 40]);
+    /// ```
+    pub fn dims(&self) -> [i32; N] {
+        self.dims
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 20, 30, 40]).unwrap();
+    /// assert_eq!(w4d.size(), 240000 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.szu
+    }
+    /// Returns the total number of cells in the grid as an `i32` number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 20, 30, 40]).unwrap();
+    /// assert_eq!(w4d.size32(), 240000);
+    /// ```
+    pub fn size32(&self) -> i32 {
+        self.sz32
+    }
+    /// Returns an index into the grid based on `coords`. Coordinates wrap around the limits of the grid
+    /// and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let ring = WrappingCoords::new([10]).unwrap();
+    /// assert_eq!(ring.index([-1]), 9);
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// assert_eq!(w4d.index([-1, -1, -1, -1]), 9999);
+    /// assert_eq!(w4d.index([i32::MAX, i32::MIN, 0, 0]), 27);
+    /// ```
+    pub fn index(&self, coords: [i32; N]) -> usize {
+        coords
+            .iter()
+            .zip(self.dims.iter().zip(self.strides.iter()))
+            .map(|(&c, (&d, &stride))| WrappingCoords2d::modulo(c, d) as usize * stride)
+            .sum()
+    }
+    /// Returns the coordinates of the cell given by an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// assert_eq!(w4d.coords(0), [0, 0, 0, 0]);
+    /// assert_eq!(w4d.coords(9876), [6, 7, 8, 9]);
+    /// ```
+    pub fn coords(&self, index: usize) -> [i32; N] {
+        let mut coords = [0; N];
+        for (axis, c) in coords.iter_mut().enumerate() {
+            *c = (index / self.strides[axis]) as i32 % self.dims[axis];
+        }
+        coords
+    }
+    /// Returns the coordinates of the cell given by an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// assert_eq!(w4d.coords_checked(9999), Some([9, 9, 9, 9]));
+    /// assert_eq!(w4d.coords_checked(10000), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<[i32; N]> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns a new index into the grid based on a starting index `start_index` and an offset along each axis.
+    /// Offsets can be negative and can take any value in the range of `i32`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// assert_eq!(w4d.shift(0, [0, 0, 0, -1]), 9000);
+    /// assert_eq!(w4d.shift(0, [i32::MAX, i32::MIN, 0, 0]), 27);
+    /// ```
+    pub fn shift(&self, start_index: usize, deltas: [i32; N]) -> usize {
+        let coords = self.coords(start_index % self.szu); // Reduced, so that invalid indices stay in the grid
+        (0..N)
+            .map(|axis| {
+                let d = self.dims[axis];
+                WrappingCoords2d::wrapping_add(coords[axis], deltas[axis], d) as usize
+                    * self.strides[axis]
+            })
+            .sum()
+    }
+    /// Returns `radius`, reduced to the sum of the dimensions of the grid. Rings beyond that distance only repeat
+    /// the cells of closer rings.
+    fn reach(&self, radius: u32) -> u32 {
+        let limit = self.dims.iter().map(|&d| d as i64).sum::<i64>();
+        radius.min(limit.min(i32::MAX as i64) as u32)
+    }
+    /// Returns the indices to the neighbors of the cell given by `start_index`, in the order of `offsets`.
+    fn neighbors_offsets(&self, start_index: usize, offsets: &[[i32; N]]) -> std::vec::Vec<usize> {
+        offsets
+            .iter()
+            .map(|&deltas| self.shift(start_index, deltas))
+            .collect()
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its Moore neighborhood
+    /// of radius `radius`: every cell whose offsets along every axis lie in the range [-radius, radius],
+    /// except the cell itself. The indices are ordered ring by ring, starting with the closest ring.
+    /// In 1D, each ring at distance `k` is ordered as `[k, -k]`. In 2D, the order is that of
+    /// [`WrappingCoords2d::neighbors_moore`]. In higher dimensions, each ring is ordered with the first axis
+    /// varying fastest, like the indices of the grid.
+    ///
+    /// Radii larger than the sum of the dimensions of the grid are reduced to that sum, because farther rings
+    /// only repeat the cells of closer rings.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let ring = WrappingCoords::new([10]).unwrap();
+    /// assert_eq!(ring.neighbors_moore(0, 2), vec![1, 9, 2, 8]);
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// let neighbors = w4d.neighbors_moore(5555, 1);
+    /// assert_eq!(neighbors.len(), 80);
+    /// assert_eq!(neighbors[0], 4444);
+    /// assert_eq!(neighbors[79], 6666);
+    /// ```
+    pub fn neighbors_moore(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &offsets(self.reach(radius), false))
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its von Neumann
+    /// neighborhood of radius `radius`: every cell whose Manhattan distance to the cell lies in the range [1, radius].
+    /// The indices are ordered ring by ring, starting with the closest ring. In 1D, each ring at distance `k`
+    /// is ordered as `[k, -k]`. In 2D, the order is that of [`WrappingCoords2d::neighbors_von_neumann`].
+    /// In higher dimensions, each ring is ordered with the first axis varying fastest, like the indices of the grid.
+    ///
+    /// Radii larger than the sum of the dimensions of the grid are reduced to that sum, because farther rings
+    /// only repeat the cells of closer rings.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([10, 10, 10, 10]).unwrap();
+    /// assert_eq!(
+    ///     w4d.neighbors_von_neumann(5555, 1),
+    ///     vec![4555, 5455, 5545, 5554, 5556, 5565, 5655, 6555]
+    /// );
+    /// ```
+    pub fn neighbors_von_neumann(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &offsets(self.reach(radius), true))
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and the neighbors given by `offsets`.
+    /// Unlike `WrappingCoords2d::for_each`, the shift along each axis has the form (x * stride + span), where span is
+    /// the stride of the next axis, so the loop only adds to and reduces usize values, without converting from i32.
+    fn for_each_offsets<F>(&self, offsets: &[[i32; N]], mut f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        let mut spans = [0; N];
+        for (axis, span) in spans.iter_mut().enumerate() {
+            *span = self.strides[axis] * self.dims[axis] as usize;
+        }
+        let shifts0: std::vec::Vec<[usize; N]> = offsets
+            .iter()
+            .map(|deltas| {
+                let mut shifts = [0; N];
+                for (axis, shift) in shifts.iter_mut().enumerate() {
+                    *shift = WrappingCoords2d::modulo(deltas[axis], self.dims[axis]) as usize
+                        * self.strides[axis]
+                        + spans[axis];
+                }
+                shifts
+            })
+            .collect();
+        let mut shifts = shifts0.clone();
+        let mut neighbors = vec![0; offsets.len()];
+        let mut this_cell_index = 0;
+        loop {
+            for (neighbor, shift) in neighbors.iter_mut().zip(shifts.iter()) {
+                *neighbor = (0..N).map(|axis| shift[axis] % spans[axis]).sum();
+            }
+            // Evaluation
+            f(this_cell_index, &neighbors);
+            // Next iteration
+            this_cell_index += 1;
+            if this_cell_index == self.szu {
+                break;
+            }
+            // Locate the neighbors: advance along the slowest axis whose stride divides the index,
+            // and restart the faster axes
+            let axis = (0..N)
+                .rev()
+                .find(|&axis| this_cell_index % self.strides[axis] == 0)
+                .unwrap_or(0); // strides[0] == 1 divides every index
+            for (shift, shift0) in shifts.iter_mut().zip(shifts0.iter()) {
+                shift[..axis].copy_from_slice(&shift0[..axis]);
+                shift[axis] += self.strides[axis];
+            }
+        }
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its Moore neighborhood of radius `radius`,
+    /// ordered as in [`neighbors_moore`](#method.neighbors_moore).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([5, 5, 5, 5]).unwrap();
+    /// let mut calls_counter = 0;
+    /// w4d.for_each_moore(1, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 80);
+    ///     assert_eq!(neighbors[0], w4d.shift(this_cell_index, [-1, -1, -1, -1]));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w4d.size());
+    /// ```
+    pub fn for_each_moore<F>(&self, radius: u32, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.for_each_offsets(&offsets(self.reach(radius), false), f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its von Neumann neighborhood
+    /// of radius `radius`, ordered as in [`neighbors_von_neumann`](#method.neighbors_von_neumann).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords;
+    /// let w4d = WrappingCoords::new([5, 5, 5, 5]).unwrap();
+    /// let mut calls_counter = 0;
+    /// w4d.for_each_von_neumann(1, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 8);
+    ///     assert_eq!(neighbors[7], w4d.shift(this_cell_index, [0, 0, 0, 1]));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w4d.size());
+    /// ```
+    pub fn for_each_von_neumann<F>(&self, radius: u32, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.for_each_offsets(&offsets(self.reach(radius), true), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WrappingCoords3d;
+
+    #[test]
+    fn grids_nd() {
+        assert!(matches!(
+            WrappingCoords::new([1, 0]),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingCoords::new([2, 1, 1, i32::MAX]),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(WrappingCoords::new([1, 1, 1, i32::MAX]).is_ok());
+        // 2D grids agree with WrappingCoords2d on every query
+        for &(w, h) in [(10, 10), (7, 4), (1, 1), (2, 5), (1, 9)].iter() {
+            let w2d = WrappingCoords2d::new(w, h).unwrap();
+            let wnd = WrappingCoords::new([w, h]).unwrap();
+            assert_eq!(wnd.size(), w2d.size());
+            for index in 0..w2d.size() {
+                let (x, y) = w2d.coords(index);
+                assert_eq!(wnd.coords(index), [x, y]);
+                assert_eq!(wnd.index([x - 3 * w, y + h]), index);
+                for &(dx, dy) in [(1, 2), (-3, 0), (i32::MIN, i32::MAX)].iter() {
+                    assert_eq!(wnd.shift(index, [dx, dy]), w2d.shift(index, dx, dy));
+                }
+                for radius in 0..4 {
+                    assert_eq!(
+                        wnd.neighbors_moore(index, radius),
+                        w2d.neighbors_moore(index, radius)
+                    );
+                    assert_eq!(
+                        wnd.neighbors_von_neumann(index, radius),
+                        w2d.neighbors_von_neumann(index, radius)
+                    );
+                }
+            }
+            let mut calls_counter = 0;
+            wnd.for_each_moore(2, |this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w2d.neighbors24(this_cell_index)[..]);
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, w2d.size());
+        }
+        // 3D grids agree with WrappingCoords3d on indices and coordinates
+        let w3d = WrappingCoords3d::new(4, 3, 5).unwrap();
+        let wnd = WrappingCoords::new([4, 3, 5]).unwrap();
+        for index in 0..w3d.size() {
+            let (x, y, z) = w3d.coords(index);
+            assert_eq!(wnd.coords(index), [x, y, z]);
+            assert_eq!(wnd.shift(index, [-1, 2, 7]), w3d.shift(index, -1, 2, 7));
+        }
+        // Neighborhood sizes and the engine in 1D, 3D and 4D
+        let ring = WrappingCoords::new([9]).unwrap();
+        assert_eq!(ring.neighbors_von_neumann(4, 3), vec![5, 3, 6, 2, 7, 1]);
+        assert_eq!(ring.neighbors_moore(4, 3), ring.neighbors_von_neumann(4, 3));
+        for radius in 0..3 {
+            let r = radius as usize;
+            assert_eq!(offsets::<3>(radius, false).len(), (2 * r + 1).pow(3) - 1);
+            assert_eq!(offsets::<4>(radius, false).len(), (2 * r + 1).pow(4) - 1);
+        }
+        // The von Neumann neighborhood of radius 1 has 2N cells; radius 2 adds 2N + 4 * N(N - 1) / 2 cells
+        assert_eq!(offsets::<4>(1, true).len(), 8);
+        assert_eq!(offsets::<4>(2, true).len(), 8 + 8 + 24);
+        for &dims in [[3, 4, 2, 5], [1, 1, 1, 1], [2, 1, 3, 1]].iter() {
+            let w4d = WrappingCoords::new(dims).unwrap();
+            for index in 0..w4d.size() {
+                assert_eq!(w4d.index(w4d.coords(index)), index);
+            }
+            for radius in 0..3 {
+                let mut calls_counter = 0;
+                w4d.for_each_moore(radius, |this_cell_index, neighbors| {
+                    assert_eq!(neighbors, &w4d.neighbors_moore(this_cell_index, radius)[..]);
+                    calls_counter += 1;
+                });
+                w4d.for_each_von_neumann(radius, |this_cell_index, neighbors| {
+                    assert_eq!(
+                        neighbors,
+                        &w4d.neighbors_von_neumann(this_cell_index, radius)[..]
+                    );
+                    calls_counter += 1;
+                });
+                assert_eq!(calls_counter, 2 * w4d.size());
+            }
+        }
+        // Huge radii are reduced to the sum of the dimensions
+        let w3d = WrappingCoords::new([2, 3, 1]).unwrap();
+        assert_eq!(w3d.neighbors_moore(0, u32::MAX), w3d.neighbors_moore(0, 6));
+        assert_eq!(w3d.neighbors_von_neumann(0, u32::MAX).len(), 376);
+        // Invalid start indices wrap around the grid
+        let ring = WrappingCoords::new([10]).unwrap();
+        assert_eq!(ring.shift(usize::MAX, [0]), usize::MAX % 10);
+        let w2d = WrappingCoords::new([10, 10]).unwrap();
+        for &start in [w2d.size(), 1 << 31, usize::MAX].iter() {
+            assert_eq!(w2d.shift(start, [0, 0]), start % w2d.size());
+            assert_eq!(
+                w2d.shift(start, [3, -1]),
+                w2d.shift(start % w2d.size(), [3, -1])
+            );
+            assert_eq!(
+                w2d.neighbors_moore(start, 1),
+                w2d.neighbors_moore(start % w2d.size(), 1)
+            );
+            assert_eq!(
+                w2d.neighbors_von_neumann(start, 2),
+                w2d.neighbors_von_neumann(start % w2d.size(), 2)
+            );
+        }
+    }
+}
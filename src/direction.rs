@@ -0,0 +1,452 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{WrappingCoords2d, YAxis, OFFSETS4, OFFSETS8};
+
+/// Represents one of the 8 compass directions, with y increasing to the north.
+///
+/// The directions are declared counter-clockwise, starting from `East`, which is the order of the arrays returned by
+/// [`WrappingCoords2d::neighbors8`] in the default [`NeighborOrder`](crate::NeighborOrder).
+/// [`slot`](#method.slot) returns the position of each direction in those arrays:
+///
+/// | Direction   | Offset `(dx, dy)` | Position in `neighbors8` |
+/// |-------------|-------------------|--------------------------|
+/// | `East`      | `(1, 0)`          | 0                        |
+/// | `NorthEast` | `(1, 1)`          | 1                        |
+/// | `North`     | `(0, 1)`          | 2                        |
+/// | `NorthWest` | `(-1, 1)`         | 3                        |
+/// | `West`      | `(-1, 0)`         | 4                        |
+/// | `SouthWest` | `(-1, -1)`        | 5                        |
+/// | `South`     | `(0, -1)`         | 6                        |
+/// | `SouthEast` | `(1, -1)`         | 7                        |
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{Direction, WrappingCoords2d};
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// let heading = Direction::North;
+/// assert_eq!(w2d.step(55, heading), 65);
+/// assert_eq!(w2d.step(55, heading.turn_right()), 66);
+/// assert_eq!(w2d.neighbors8(55)[Direction::NorthEast.slot()], 66);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Offset `(1, 0)`.
+    East,
+    /// Offset `(1, 1)`.
+    NorthEast,
+    /// Offset `(0, 1)`.
+    North,
+    /// Offset `(-1, 1)`.
+    NorthWest,
+    /// Offset `(-1, 0)`.
+    West,
+    /// Offset `(-1, -1)`.
+    SouthWest,
+    /// Offset `(0, -1)`.
+    South,
+    /// Offset `(1, -1)`.
+    SouthEast,
+}
+
+impl Direction {
+    /// The 8 directions, counter-clockwise, starting from `East`, in the order of `neighbors8`.
+    pub const ALL: [Direction; 8] = [
+        Direction::East,
+        Direction::NorthEast,
+        Direction::North,
+        Direction::NorthWest,
+        Direction::West,
+        Direction::SouthWest,
+        Direction::South,
+        Direction::SouthEast,
+    ];
+    /// Returns the position of the direction in the arrays returned by `neighbors8` with the default
+    /// [`NeighborOrder`](crate::NeighborOrder). Use [`WrappingCoords2d::slot`] for grids with other orders.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::East.slot(), 0);
+    /// assert_eq!(Direction::SouthEast.slot(), 7);
+    /// ```
+    pub fn slot(self) -> usize {
+        self as usize
+    }
+    /// Returns the offset `(dx, dy)` of a step in the direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::NorthWest.offset(), (-1, 1));
+    /// ```
+    pub fn offset(self) -> (i32, i32) {
+        OFFSETS8[self.slot()]
+    }
+    /// Returns the direction of the offset `(dx, dy)`, or `None` if the offset is not a single step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::from_offset((0, -1)), Some(Direction::South));
+    /// assert_eq!(Direction::from_offset((2, 0)), None);
+    /// ```
+    pub fn from_offset(offset: (i32, i32)) -> Option<Direction> {
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|d| d.offset() == offset)
+    }
+    /// Returns the direction 45 degrees counter-clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::North.turn_left(), Direction::NorthWest);
+    /// assert_eq!(Direction::SouthEast.turn_left(), Direction::East);
+    /// ```
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self.slot() + 1) % 8]
+    }
+    /// Returns the direction 45 degrees clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::North.turn_right(), Direction::NorthEast);
+    /// assert_eq!(Direction::East.turn_right(), Direction::SouthEast);
+    /// ```
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self.slot() + 7) % 8]
+    }
+    /// Returns the opposite direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction;
+    /// assert_eq!(Direction::NorthEast.opposite(), Direction::SouthWest);
+    /// ```
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self.slot() + 4) % 8]
+    }
+}
+
+/// Represents one of the 4 cardinal directions, with y increasing to the north.
+///
+/// The directions are declared counter-clockwise, starting from `East`, which is the order of the arrays returned by
+/// [`WrappingCoords2d::neighbors4`] in the default [`NeighborOrder`](crate::NeighborOrder).
+/// [`slot`](#method.slot) returns the position of each direction in those arrays:
+///
+/// | Direction4 | Offset `(dx, dy)` | Position in `neighbors4` | Position in `neighbors8` |
+/// |------------|-------------------|--------------------------|--------------------------|
+/// | `East`     | `(1, 0)`          | 0                        | 0                        |
+/// | `North`    | `(0, 1)`          | 1                        | 2                        |
+/// | `West`     | `(-1, 0)`         | 2                        | 4                        |
+/// | `South`    | `(0, -1)`         | 3                        | 6                        |
+///
+/// A `Direction4` converts into the [`Direction`] with the same name, so that every function that takes
+/// `impl Into<Direction>` takes both types.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{Direction, Direction4, WrappingCoords2d};
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// assert_eq!(w2d.step(55, Direction4::West), 54);
+/// assert_eq!(w2d.neighbors4(55)[Direction4::West.slot()], 54);
+/// assert_eq!(Direction::from(Direction4::West), Direction::West);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    /// Offset `(1, 0)`.
+    East,
+    /// Offset `(0, 1)`.
+    North,
+    /// Offset `(-1, 0)`.
+    West,
+    /// Offset `(0, -1)`.
+    South,
+}
+
+impl Direction4 {
+    /// The 4 directions, counter-clockwise, starting from `East`, in the order of `neighbors4`.
+    pub const ALL: [Direction4; 4] = [
+        Direction4::East,
+        Direction4::North,
+        Direction4::West,
+        Direction4::South,
+    ];
+    /// Returns the position of the direction in the arrays returned by `neighbors4` with the default
+    /// [`NeighborOrder`](crate::NeighborOrder). Use [`WrappingCoords2d::slot4`] for grids with other orders.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction4;
+    /// assert_eq!(Direction4::North.slot(), 1);
+    /// ```
+    pub fn slot(self) -> usize {
+        self as usize
+    }
+    /// Returns the offset `(dx, dy)` of a step in the direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction4;
+    /// assert_eq!(Direction4::South.offset(), (0, -1));
+    /// ```
+    pub fn offset(self) -> (i32, i32) {
+        OFFSETS4[self.slot()]
+    }
+    /// Returns the direction 90 degrees counter-clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction4;
+    /// assert_eq!(Direction4::North.turn_left(), Direction4::West);
+    /// assert_eq!(Direction4::South.turn_left(), Direction4::East);
+    /// ```
+    pub fn turn_left(self) -> Direction4 {
+        Direction4::ALL[(self.slot() + 1) % 4]
+    }
+    /// Returns the direction 90 degrees clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction4;
+    /// assert_eq!(Direction4::North.turn_right(), Direction4::East);
+    /// ```
+    pub fn turn_right(self) -> Direction4 {
+        Direction4::ALL[(self.slot() + 3) % 4]
+    }
+    /// Returns the opposite direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Direction4;
+    /// assert_eq!(Direction4::East.opposite(), Direction4::West);
+    /// ```
+    pub fn opposite(self) -> Direction4 {
+        Direction4::ALL[(self.slot() + 2) % 4]
+    }
+}
+
+impl From<Direction4> for Direction {
+    fn from(direction: Direction4) -> Self {
+        Direction::ALL[2 * direction.slot()]
+    }
+}
+
+impl WrappingCoords2d {
+    /// Returns the index of the neighbor in `direction` of the cell given by `start_index`.
+    /// This is `shift` with the offset of `direction`, so it follows the boundaries and the topology of the grid.
+    ///
+    /// Directions follow the y axis of the [`NeighborOrder`](crate::NeighborOrder) of the grid: with `YAxis::Down`,
+    /// `Direction::North` moves by `(0, -1)`, towards the top of the screen.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the neighbor lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`shift_checked`](#method.shift_checked) with [`Direction::offset`] on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, Direction4, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.step(95, Direction::NorthEast), 6);
+    /// assert_eq!(w2d.step(0, Direction4::South), 90);
+    /// ```
+    pub fn step(&self, start_index: usize, direction: impl Into<Direction>) -> usize {
+        let (dx, dy) = self.screen_offset(direction.into());
+        self.shift(start_index, dx, dy)
+    }
+    /// Returns the direction of a step from the cell given by `from_index` to the cell given by `to_index`,
+    /// or `None` if the cells are not adjacent in the 8-neighborhood. In small grids, where several steps lead
+    /// to the same cell, this function returns the first of them, counter-clockwise, starting from `East`.
+    /// Directions follow the y axis of the grid, as in [`step`](#method.step).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `from_index` and `to_index` are valid indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.direction_between(95, 6), Some(Direction::NorthEast));
+    /// assert_eq!(w2d.direction_between(0, 99), Some(Direction::SouthWest));
+    /// assert_eq!(w2d.direction_between(0, 55), None);
+    /// assert_eq!(w2d.direction_between(0, 0), None);
+    /// ```
+    pub fn direction_between(&self, from_index: usize, to_index: usize) -> Option<Direction> {
+        Direction::ALL.iter().copied().find(|&d| {
+            let (dx, dy) = self.screen_offset(d);
+            self.shift_checked(from_index, dx, dy) == Some(to_index)
+        })
+    }
+    /// Returns the position of `direction` in the arrays returned by `neighbors8` and in the first 8 neighbors
+    /// of `neighbors24`, following the [`NeighborOrder`](crate::NeighborOrder) of the grid. Unlike
+    /// [`Direction::slot`], this function honors orders other than the default one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, NeighborOrder, Rotation, WrappingCoords2d, YAxis};
+    /// let order = NeighborOrder {
+    ///     start: Direction::North,
+    ///     rotation: Rotation::Clockwise,
+    ///     y_axis: YAxis::Down,
+    /// };
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap().with_order(order);
+    /// assert_eq!(w2d.slot(Direction::North), 0);
+    /// assert_eq!(w2d.slot(Direction::East), 2);
+    /// let neighbors = w2d.neighbors8_array(55);
+    /// assert_eq!(neighbors[w2d.slot(Direction::SouthWest)], w2d.step(55, Direction::SouthWest));
+    /// // With the default order, this is Direction::slot:
+    /// assert_eq!(WrappingCoords2d::new(10, 10).unwrap().slot(Direction::East), Direction::East.slot());
+    /// ```
+    pub fn slot(&self, direction: impl Into<Direction>) -> usize {
+        let offset = self.screen_offset(direction.into());
+        // The 8-neighborhood contains the offsets of all directions
+        self.offsets.n8.iter().position(|&o| o == offset).unwrap()
+    }
+    /// Returns the position of `direction` in the arrays returned by `neighbors4`, following the
+    /// [`NeighborOrder`](crate::NeighborOrder) of the grid. Unlike [`Direction4::slot`], this function honors orders
+    /// other than the default one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, Direction4, NeighborOrder, Rotation, WrappingCoords2d};
+    /// let order = NeighborOrder {
+    ///     start: Direction::North,
+    ///     rotation: Rotation::Clockwise,
+    ///     ..NeighborOrder::default()
+    /// };
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap().with_order(order);
+    /// assert_eq!(w2d.slot4(Direction4::North), 0);
+    /// assert_eq!(w2d.slot4(Direction4::West), 3);
+    /// assert_eq!(w2d.neighbors4(55)[w2d.slot4(Direction4::East)], 56);
+    /// ```
+    pub fn slot4(&self, direction: Direction4) -> usize {
+        let offset = self.screen_offset(direction.into());
+        // The 4-neighborhood contains the offsets of all 4 directions
+        self.offsets.n4.iter().position(|&o| o == offset).unwrap()
+    }
+    /// Returns the offset of `direction` in grid coordinates, following the y axis of the neighbor order.
+    fn screen_offset(&self, direction: Direction) -> (i32, i32) {
+        let (dx, dy) = direction.offset();
+        match self.order.y_axis {
+            YAxis::Up => (dx, dy),
+            YAxis::Down => (dx, -dy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, NeighborOrder, Rotation, Topology};
+
+    #[test]
+    fn directions() {
+        for &d in Direction::ALL.iter() {
+            assert_eq!(Direction::from_offset(d.offset()), Some(d));
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(
+                d.turn_left().turn_left().turn_left().turn_left(),
+                d.opposite()
+            );
+            assert_eq!(d.opposite().opposite(), d);
+            let (dx, dy) = d.offset();
+            assert_eq!(d.opposite().offset(), (-dx, -dy));
+        }
+        for &d in Direction4::ALL.iter() {
+            assert_eq!(Direction::from(d).offset(), d.offset());
+            assert_eq!(
+                Direction::from(d.turn_left()),
+                Direction::from(d).turn_left().turn_left()
+            );
+            assert_eq!(
+                Direction::from(d.turn_right()),
+                Direction::from(d).turn_right().turn_right()
+            );
+            assert_eq!(Direction::from(d.opposite()), Direction::from(d).opposite());
+        }
+        let grids = [
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(4, 3).unwrap(),
+            WrappingCoords2d::new_with_topology(6, 5, Topology::KleinBottle).unwrap(),
+        ];
+        for w2d in grids.iter() {
+            for index in 0..w2d.size() {
+                let neighbors4 = w2d.neighbors4_array(index);
+                let neighbors8 = w2d.neighbors8_array(index);
+                for &d in Direction4::ALL.iter() {
+                    assert_eq!(w2d.step(index, d), neighbors4[d.slot()]);
+                }
+                for &d in Direction::ALL.iter() {
+                    let neighbor = w2d.step(index, d);
+                    assert_eq!(neighbor, neighbors8[d.slot()]);
+                    assert_eq!(
+                        w2d.step(index, w2d.direction_between(index, neighbor).unwrap()),
+                        neighbor
+                    );
+                }
+            }
+        }
+        // On a cylinder that absorbs north-south, steps beyond the edge have no direction
+        let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb)
+            .unwrap();
+        assert_eq!(w2d.direction_between(95, 5), None);
+        assert_eq!(w2d.direction_between(95, 96), Some(Direction::East));
+        // With y increasing downwards, north is at the top of the screen, as in the neighbor order
+        let order = NeighborOrder {
+            start: Direction::North,
+            rotation: Rotation::Clockwise,
+            y_axis: YAxis::Down,
+        };
+        let w2d = WrappingCoords2d::new(7, 6).unwrap().with_order(order);
+        assert_eq!(w2d.step(24, Direction::North), 17);
+        assert_eq!(w2d.step(24, Direction4::South), 31);
+        for index in 0..w2d.size() {
+            let mut heading = Direction::North;
+            for &neighbor in w2d.neighbors8(index).iter() {
+                assert_eq!(w2d.step(index, heading), neighbor);
+                assert_eq!(w2d.direction_between(index, neighbor), Some(heading));
+                heading = heading.turn_right();
+            }
+        }
+    }
+}
@@ -0,0 +1,681 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, OFFSETS16, OFFSETS24, OFFSETS4, OFFSETS8};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer types that can hold the width, height, and coordinates of a [`GenericCoords2d`] grid:
+/// `i16`, `u16`, `i32`, `u32`, `i64`, `u64`, `isize`, and `usize`.
+///
+/// This trait is sealed; it cannot be implemented outside of this crate.
+pub trait GridInt: Copy + std::fmt::Debug + PartialEq + sealed::Sealed {
+    /// Converts the number to `i128`, which holds every value of every `GridInt` type.
+    fn to_i128(self) -> i128;
+    /// Converts a number in the range of `Self` from `i128`.
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! grid_int {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+            impl GridInt for $t {
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+                fn from_i128(value: i128) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+grid_int!(i16, u16, i32, u32, i64, u64, isize, usize);
+
+/// Represents a 2D grid with wrapping, whose width, height, and coordinates are of the integer type `T`.
+///
+/// `WrappingCoords2d` uses `i32` and limits the number of cells to `std::i32::MAX`. `GenericCoords2d<i64>`
+/// supports grids with more cells, up to `usize::MAX`, while `GenericCoords2d<i16>` and `GenericCoords2d<u16>`
+/// keep coordinates compact. Indices into the 1D container are always `usize`, and they follow the layout of
+/// `WrappingCoords2d`: the index of the cell at `(x, y)` is `y * width + x`. Neighbors are ordered as in
+/// `WrappingCoords2d`. Internally, every computation is free of overflow.
+///
+/// Offsets are of type `T` too. For unsigned types, move left or down by adding `width - 1` or `height - 1`.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::WrappingCoords2d64;
+/// // A grid with more than std::i32::MAX cells
+/// let w2d = WrappingCoords2d64::new(100_000, 100_000).unwrap();
+/// assert_eq!(w2d.size(), 10_000_000_000);
+/// assert_eq!(w2d.index(-1, -1), 9_999_999_999);
+/// assert_eq!(w2d.coords(9_999_999_999), (99_999, 99_999));
+/// assert_eq!(w2d.shift(9_999_999_999, 1, 1), 0);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct GenericCoords2d<T: GridInt> {
+    /// Width of the grid; it has to be larger than 0.
+    w: T,
+    /// Height of the grid; it has to be larger than 0.
+    h: T,
+    /// Width of the grid.
+    wu: usize,
+    /// Total number of cells in the grid; it has to be larger than 0.
+    szu: usize,
+}
+
+/// A 2D grid with `i64` coordinates, for grids with more than `std::i32::MAX` cells.
+pub type WrappingCoords2d64 = GenericCoords2d<i64>;
+
+/// A 2D grid with `i16` coordinates, for compact storage of coordinates.
+pub type WrappingCoords2d16 = GenericCoords2d<i16>;
+
+impl<T: GridInt> GenericCoords2d<T> {
+    /// Constructs a new GenericCoords2d object.
+    ///
+    /// # Errors
+    ///
+    /// Both `width` and `height` must be larger than 0. Also, their product must not exceed `usize::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, GenericCoords2d};
+    /// assert!(GenericCoords2d::<u16>::new(u16::MAX, u16::MAX).is_ok());
+    /// assert!(matches!(GenericCoords2d::<u16>::new(0, 10), Err(ErrorKind::DimensionsLessThan1)));
+    /// assert!(matches!(GenericCoords2d::<i64>::new(i64::MAX, i64::MAX), Err(ErrorKind::DimensionsTooLarge)));
+    /// assert!(matches!(GenericCoords2d::<u64>::new(u64::MAX, u64::MAX), Err(ErrorKind::DimensionsTooLarge)));
+    /// ```
+    pub fn new(width: T, height: T) -> Result<GenericCoords2d<T>, ErrorKind> {
+        let (w, h) = (width.to_i128(), height.to_i128());
+        if w < 1 || h < 1 {
+            return Err(ErrorKind::DimensionsLessThan1);
+        }
+        // Two 64-bit factors can overflow i128
+        let s = match w.checked_mul(h) {
+            Some(s) if s <= usize::MAX as i128 => s,
+            _ => return Err(ErrorKind::DimensionsTooLarge),
+        };
+        Ok(GenericCoords2d {
+            w: width,
+            h: height,
+            wu: w as usize,
+            szu: s as usize,
+        })
+    }
+    /// Returns the width of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 20).unwrap();
+    /// assert_eq!(w2d.width(), 10);
+    /// ```
+    pub fn width(&self) -> T {
+        self.w
+    }
+    /// Returns the height of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 20).unwrap();
+    /// assert_eq!(w2d.height(), 20);
+    /// ```
+    pub fn height(&self) -> T {
+        self.h
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(300, 300).unwrap();
+    /// assert_eq!(w2d.size(), 90000 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.szu
+    }
+    /// Returns the remainder of `lhs` divided by `rhs`, in the range [0, rhs), as [`WrappingCoords2d::modulo`]
+    /// does for `i32`. `rhs` must be larger than 0.
+    ///
+    /// [`WrappingCoords2d::modulo`]: crate::WrappingCoords2d::modulo
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{WrappingCoords2d16, WrappingCoords2d64};
+    /// assert_eq!(WrappingCoords2d64::modulo(-11, 10), 9);
+    /// assert_eq!(WrappingCoords2d16::modulo(i16::MIN, 10), 2);
+    /// ```
+    pub fn modulo(lhs: T, rhs: T) -> T {
+        T::from_i128(lhs.to_i128().rem_euclid(rhs.to_i128()))
+    }
+    /// Returns an index into the grid based on `x` and `y` coordinates. Coordinates wrap around the limits of the grid
+    /// and can take any value in the range of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// assert_eq!(w2d.index(5, 9), 95);
+    /// assert_eq!(w2d.index(-1, -1), 99);
+    /// assert_eq!(w2d.index(i16::MAX, i16::MIN), 27);
+    /// ```
+    pub fn index(&self, x: T, y: T) -> usize {
+        self.locate(x.to_i128(), y.to_i128())
+    }
+    /// Returns the index of the cell at `(x, y)`, which can lie beyond the range of `T`.
+    fn locate(&self, x: i128, y: i128) -> usize {
+        let mx = x.rem_euclid(self.w.to_i128()) as usize;
+        let my = y.rem_euclid(self.h.to_i128()) as usize;
+        my * self.wu + mx // Smaller than size; no overflow
+    }
+    /// Returns `x` and `y` coordinates based on an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// assert_eq!(w2d.coords(95), (5, 9));
+    /// ```
+    pub fn coords(&self, index: usize) -> (T, T) {
+        (
+            T::from_i128((index % self.wu) as i128),
+            T::from_i128((index / self.wu) as i128),
+        )
+    }
+    /// Returns `x` and `y` coordinates based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// assert_eq!(w2d.coords_checked(99), Some((9, 9)));
+    /// assert_eq!(w2d.coords_checked(100), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<(T, T)> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, and x and y offsets.
+    /// Offsets can take any value in the range of `T`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::GenericCoords2d;
+    /// let w2d = GenericCoords2d::<u16>::new(10, 10).unwrap();
+    /// assert_eq!(w2d.shift(0, 1, 1), 11);
+    /// // Unsigned offsets move left and down by wrapping around the grid
+    /// assert_eq!(w2d.shift(0, 9, 9), 99);
+    /// ```
+    pub fn shift(&self, start_index: usize, delta_x: T, delta_y: T) -> usize {
+        self.shift_i128(start_index, delta_x.to_i128(), delta_y.to_i128())
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, and offsets in `i128`.
+    fn shift_i128(&self, start_index: usize, delta_x: i128, delta_y: i128) -> usize {
+        let (x, y) = (
+            (start_index % self.wu) as i128,
+            (start_index / self.wu) as i128,
+        );
+        self.locate(x + delta_x, y + delta_y)
+    }
+    /// Returns the indices to the neighbors of the cell given by `start_index`, in the order of `offsets`.
+    fn neighbors_array<const N: usize>(
+        &self,
+        start_index: usize,
+        offsets: &[(i32, i32); N],
+    ) -> [usize; N] {
+        offsets.map(|(dx, dy)| self.shift_i128(start_index, dx.into(), dy.into()))
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 4 neighbors,
+    /// ordered as in [`WrappingCoords2d::neighbors4`](crate::WrappingCoords2d::neighbors4).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors4(0), vec![1, 10, 9, 90]);
+    /// ```
+    pub fn neighbors4(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors4_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 4 neighbors,
+    /// in the same order as [`neighbors4`](#method.neighbors4). Unlike `neighbors4`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors4_array(0), [1, 10, 9, 90]);
+    /// ```
+    pub fn neighbors4_array(&self, start_index: usize) -> [usize; 4] {
+        self.neighbors_array(start_index, &OFFSETS4)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 8 neighbors,
+    /// ordered as in [`WrappingCoords2d::neighbors8`](crate::WrappingCoords2d::neighbors8).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors8(0), vec![1, 11, 10, 19, 9, 99, 90, 91]);
+    /// ```
+    pub fn neighbors8(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors8_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 8 neighbors,
+    /// in the same order as [`neighbors8`](#method.neighbors8). Unlike `neighbors8`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors8_array(0), [1, 11, 10, 19, 9, 99, 90, 91]);
+    /// ```
+    pub fn neighbors8_array(&self, start_index: usize) -> [usize; 8] {
+        self.neighbors_array(start_index, &OFFSETS8)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 16 second neighbors,
+    /// ordered as in [`WrappingCoords2d::neighbors16`](crate::WrappingCoords2d::neighbors16).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors16(55)[0], 57);
+    /// ```
+    pub fn neighbors16(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors16_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 16 second neighbors,
+    /// in the same order as [`neighbors16`](#method.neighbors16). Unlike `neighbors16`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors16_array(55)[4], 75);
+    /// ```
+    pub fn neighbors16_array(&self, start_index: usize) -> [usize; 16] {
+        self.neighbors_array(start_index, &OFFSETS16)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 24 neighbors,
+    /// ordered as in [`WrappingCoords2d::neighbors24`](crate::WrappingCoords2d::neighbors24).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors24(55)[..8], w2d.neighbors8(55));
+    /// assert_eq!(w2d.neighbors24(55)[8..], w2d.neighbors16(55));
+    /// ```
+    pub fn neighbors24(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors24_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 24 neighbors,
+    /// in the same order as [`neighbors24`](#method.neighbors24). Unlike `neighbors24`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d64;
+    /// let w2d = WrappingCoords2d64::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors24_array(55)[8], 57);
+    /// ```
+    pub fn neighbors24_array(&self, start_index: usize) -> [usize; 24] {
+        self.neighbors_array(start_index, &OFFSETS24)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and the neighbors given by `offsets`.
+    /// Unlike `WrappingCoords2d::for_each`, this function keeps the coordinates of the neighbors in the range [0, width)
+    /// and [0, size) instead of using remainders, because the shifts would overflow `usize` for the largest grids.
+    fn for_each<F, const N: usize>(&self, mut f: F, offsets: &[(i32, i32); N])
+    where
+        F: FnMut(usize, &[usize; N]),
+    {
+        let (w, h) = (self.w.to_i128(), self.h.to_i128());
+        let xs0 = offsets.map(|(dx, _)| (dx as i128).rem_euclid(w) as usize);
+        let mut neighbors = [0; N];
+        let mut this_cell_index = 0;
+        let mut y = 0;
+        while this_cell_index < self.szu {
+            let mut xs = xs0;
+            let yws = offsets.map(|(_, dy)| (y + dy as i128).rem_euclid(h) as usize * self.wu);
+            for _ in 0..self.wu {
+                for (j, neighbor) in neighbors.iter_mut().enumerate() {
+                    *neighbor = yws[j] + xs[j];
+                }
+                f(this_cell_index, &neighbors);
+                this_cell_index += 1;
+                for x in xs.iter_mut() {
+                    *x += 1;
+                    if *x == self.wu {
+                        *x = 0;
+                    }
+                }
+            }
+            y += 1;
+        }
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 4 neighbors,
+    /// ordered as in [`neighbors4`](#method.neighbors4).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each4(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], w2d.shift(this_cell_index, 1, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each4<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 4]),
+    {
+        self.for_each(f, &OFFSETS4)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 8 neighbors,
+    /// ordered as in [`neighbors8`](#method.neighbors8).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each8(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[1], w2d.shift(this_cell_index, 1, 1));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each8<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 8]),
+    {
+        self.for_each(f, &OFFSETS8)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 16 second neighbors,
+    /// ordered as in [`neighbors16`](#method.neighbors16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each16(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], w2d.shift(this_cell_index, 2, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each16<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 16]),
+    {
+        self.for_each(f, &OFFSETS16)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 24 neighbors,
+    /// ordered as in [`neighbors24`](#method.neighbors24).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each24(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[8], w2d.shift(this_cell_index, 2, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each24<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 24]),
+    {
+        self.for_each(f, &OFFSETS24)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 4 neighbors,
+    /// ordered as in [`neighbors4`](#method.neighbors4).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_pair4(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index != neighbor_index);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 4 * w2d.size());
+    /// ```
+    pub fn for_each_pair4<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each4(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 8 neighbors,
+    /// ordered as in [`neighbors8`](#method.neighbors8).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d16;
+    /// let w2d = WrappingCoords2d16::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_pair8(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index != neighbor_index);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 8 * w2d.size());
+    /// ```
+    pub fn for_each_pair8<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each8(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WrappingCoords2d;
+
+    #[test]
+    fn generic_grids() {
+        // i32 and i16 grids agree with WrappingCoords2d
+        for &(w, h) in [(10, 10), (7, 4), (1, 1), (2, 5), (9, 1)].iter() {
+            let w2d = WrappingCoords2d::new(w, h).unwrap();
+            let g32 = GenericCoords2d::<i32>::new(w, h).unwrap();
+            let g16 = WrappingCoords2d16::new(w as i16, h as i16).unwrap();
+            let gu = GenericCoords2d::<usize>::new(w as usize, h as usize).unwrap();
+            assert_eq!(g32.size(), w2d.size());
+            for index in 0..w2d.size() {
+                let (x, y) = w2d.coords(index);
+                assert_eq!(g32.coords(index), (x, y));
+                assert_eq!(g16.coords(index), (x as i16, y as i16));
+                assert_eq!(g32.index(x - 2 * w, y + h), index);
+                for &(dx, dy) in [(1, 2), (-3, 0), (i32::MIN, i32::MAX)].iter() {
+                    assert_eq!(g32.shift(index, dx, dy), w2d.shift(index, dx, dy));
+                }
+                assert_eq!(
+                    g16.shift(index, i16::MIN, i16::MAX),
+                    w2d.shift(index, i16::MIN.into(), i16::MAX.into())
+                );
+                assert_eq!(
+                    gu.shift(index, usize::MAX, 1),
+                    w2d.shift(index, (usize::MAX % w as usize) as i32, 1)
+                );
+                assert_eq!(g32.neighbors4_array(index), w2d.neighbors4_array(index));
+                assert_eq!(g16.neighbors8_array(index), w2d.neighbors8_array(index));
+                assert_eq!(gu.neighbors16_array(index), w2d.neighbors16_array(index));
+                assert_eq!(g32.neighbors24(index), w2d.neighbors24(index));
+            }
+            let mut calls_counter = 0;
+            gu.for_each4(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w2d.neighbors4_array(this_cell_index));
+                calls_counter += 1;
+            });
+            g16.for_each8(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w2d.neighbors8_array(this_cell_index));
+                calls_counter += 1;
+            });
+            g32.for_each16(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w2d.neighbors16_array(this_cell_index));
+                calls_counter += 1;
+            });
+            g32.for_each24(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &w2d.neighbors24_array(this_cell_index));
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, 4 * w2d.size());
+        }
+        // Grids beyond std::i32::MAX cells
+        assert!(matches!(
+            WrappingCoords2d::new(100_000, 100_000),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        let w = 3_000_000_000i64;
+        let g64 = WrappingCoords2d64::new(w, 4).unwrap();
+        assert_eq!(g64.size(), 12_000_000_000);
+        let last = g64.size() - 1;
+        let wu = w as usize;
+        assert_eq!(g64.coords(last), (w - 1, 3));
+        assert_eq!(g64.index(w - 1, 3), last);
+        assert_eq!(
+            g64.index(i64::MIN, i64::MAX),
+            g64.index(i64::MIN % w + w, 3)
+        );
+        assert_eq!(
+            g64.neighbors8(last),
+            vec![
+                9_000_000_000,
+                0,
+                2_999_999_999,
+                2_999_999_998,
+                last - 1,
+                last - wu - 1,
+                last - wu,
+                9_000_000_000 - wu
+            ]
+        );
+        assert!(matches!(
+            GenericCoords2d::<i16>::new(-1, 1),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            GenericCoords2d::<usize>::new(usize::MAX, 2),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(GenericCoords2d::<usize>::new(usize::MAX, 1).is_ok());
+        // The product of two 64-bit dimensions can exceed i128
+        assert!(matches!(
+            GenericCoords2d::<u64>::new(u64::MAX, u64::MAX),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(matches!(
+            GenericCoords2d::<usize>::new(usize::MAX, usize::MAX),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        // The message must not claim the i32 limit of the other grid types
+        let message = GenericCoords2d::<u64>::new(u64::MAX, u64::MAX)
+            .unwrap_err()
+            .to_string();
+        assert!(!message.contains("i32"));
+    }
+}
@@ -0,0 +1,513 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Axial offsets `(dq, dr)` of the 6 neighbors of a hexagon, counter-clockwise, starting from the right neighbor.
+const DIRECTIONS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Offsets `(dx, dy)` of the 6 neighbors of a hexagon in an even row, in the order of `DIRECTIONS`.
+const EVEN_ROW_OFFSETS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1)];
+
+/// Offsets `(dx, dy)` of the 6 neighbors of a hexagon in an odd row, in the order of `DIRECTIONS`.
+const ODD_ROW_OFFSETS: [(i32, i32); 6] = [(1, 0), (1, 1), (0, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Represents a 2D grid of hexagons with wrapping.
+///
+/// Hexagons are pointy-topped and laid out in rows. Like in [`WrappingCoords2d`], the offset coordinates `(x, y)`
+/// give the column and the row of a hexagon, the index of the hexagon is `y * width + x`, and y increases upwards.
+/// Odd rows are shifted half a hexagon to the right, so the neighbors above and below a hexagon depend on the parity
+/// of its row.
+///
+/// Moves across the grid use axial coordinates `(q, r)`, where `r = y` and `q = x - floor(y / 2)`. The six
+/// directions are the axial offsets `(1, 0)`, `(0, 1)`, `(-1, 1)`, `(-1, 0)`, `(0, -1)`, and `(1, -1)`,
+/// counter-clockwise, starting from the right neighbor.
+///
+/// # Wrapping
+///
+/// Offset coordinates wrap around the limits of the grid. Wrapping keeps the parity of each row only if
+/// `height` is even, so [`new`](#method.new) rejects odd heights. In axial coordinates, the grid repeats along
+/// the offsets `(width, 0)` and `(-height / 2, height)`.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::WrappingHex2d;
+/// let hex = WrappingHex2d::new(10, 10).unwrap();
+/// // The cell at (5, 5) lies in an odd row:
+/// assert_eq!(hex.neighbors6(55), vec![56, 66, 65, 54, 45, 46]);
+/// // The cell at (5, 4) lies in an even row:
+/// assert_eq!(hex.neighbors6(45), vec![46, 55, 54, 44, 34, 35]);
+/// assert_eq!(hex.axial(55), (3, 5));
+/// assert_eq!(hex.index_axial(3, 5), 55);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct WrappingHex2d {
+    /// Width of the grid; it has to be larger than 0.
+    w32: i32,
+    /// Height of the grid; it has to be larger than 0 and even.
+    h32: i32,
+    /// Total number of cells in the grid; it has to be larger than 0 and smaller than std::i32::MAX.
+    sz32: i32,
+    /// Width of the grid.
+    wu: usize,
+    /// Total number of cells in the grid.
+    szu: usize,
+}
+
+impl WrappingHex2d {
+    /// Constructs a new WrappingHex2d object.
+    ///
+    /// # Errors
+    ///
+    /// `width` and `height` must be larger than 0, and their product must be smaller than `std::i32::MAX`;
+    /// see [`WrappingCoords2d::new`]. Also, `height` must be even; otherwise, this function returns
+    /// `ErrorKind::DimensionsNotEven`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingHex2d};
+    /// assert!(WrappingHex2d::new(7, 10).is_ok());
+    /// assert!(matches!(WrappingHex2d::new(10, 7), Err(ErrorKind::DimensionsNotEven)));
+    /// assert!(matches!(WrappingHex2d::new(0, 10), Err(ErrorKind::DimensionsLessThan1)));
+    /// ```
+    pub fn new(width: i32, height: i32) -> Result<WrappingHex2d, ErrorKind> {
+        let w2d = WrappingCoords2d::new(width, height)?;
+        if height % 2 != 0 {
+            return Err(ErrorKind::DimensionsNotEven);
+        }
+        Ok(WrappingHex2d {
+            w32: width,
+            h32: height,
+            sz32: w2d.size32(),
+            wu: width as usize,
+            szu: w2d.size(),
+        })
+    }
+    /// Returns the width of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 20).unwrap();
+    /// assert_eq!(hex.width(), 10);
+    /// ```
+    pub fn width(&self) -> i32 {
+        self.w32
+    }
+    /// Returns the height of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 20).unwrap();
+    /// assert_eq!(hex.height(), 20);
+    /// ```
+    pub fn height(&self) -> i32 {
+        self.h32
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 20).unwrap();
+    /// assert_eq!(hex.size(), 200 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.szu
+    }
+    /// Returns the total number of cells in the grid as an `i32` number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 20).unwrap();
+    /// assert_eq!(hex.size32(), 200);
+    /// ```
+    pub fn size32(&self) -> i32 {
+        self.sz32
+    }
+    /// Returns an index into the grid based on the offset coordinates `x` and `y`. Coordinates wrap around the limits
+    /// of the grid and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.index(5, 9), 95);
+    /// assert_eq!(hex.index(-1, -1), 99);
+    /// ```
+    pub fn index(&self, x: i32, y: i32) -> usize {
+        let mx = WrappingCoords2d::modulo(x, self.w32);
+        let my = WrappingCoords2d::modulo(y, self.h32);
+        (my * self.w32 + mx) as usize // Smaller than size; no overflow
+    }
+    /// Returns the offset coordinates `x` and `y` based on an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.coords(95), (5, 9));
+    /// ```
+    pub fn coords(&self, index: usize) -> (i32, i32) {
+        let idx32 = index as i32; // Always positive
+        (idx32 % self.w32, idx32 / self.w32)
+    }
+    /// Returns the offset coordinates `x` and `y` based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.coords_checked(99), Some((9, 9)));
+    /// assert_eq!(hex.coords_checked(100), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<(i32, i32)> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns the axial coordinates `(q, r)` of the cell given by `index`, where `r = y` and `q = x - floor(y / 2)`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. However, it returns the coordinates of a cell
+    /// of the grid: invalid indices wrap around the size of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.axial(0), (0, 0));
+    /// assert_eq!(hex.axial(95), (1, 9));
+    /// ```
+    pub fn axial(&self, index: usize) -> (i32, i32) {
+        let (x, y) = self.coords(index % self.szu); // Reduced, so that invalid indices stay in the grid
+        (x - y / 2, y) // y is positive
+    }
+    /// Returns an index into the grid based on the axial coordinates `q` and `r`. Coordinates wrap around
+    /// the limits of the grid and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.index_axial(1, 9), 95);
+    /// // The grid repeats along the axial offsets (width, 0) and (-height / 2, height):
+    /// assert_eq!(hex.index_axial(11, 9), 95);
+    /// assert_eq!(hex.index_axial(-4, 19), 95);
+    /// ```
+    pub fn index_axial(&self, q: i32, r: i32) -> usize {
+        self.locate(q as i64, r as i64)
+    }
+    /// Returns the index of the cell at the axial coordinates `q` and `r`, which can lie beyond the range of `i32`.
+    fn locate(&self, q: i64, r: i64) -> usize {
+        let x = (q + r.div_euclid(2)).rem_euclid(self.w32 as i64);
+        let y = r.rem_euclid(self.h32 as i64);
+        (y * self.w32 as i64 + x) as usize // Smaller than size; no overflow
+    }
+    /// Returns a new index into the grid based on a starting index `start_index` and the axial offsets `delta_q` and
+    /// `delta_r`. For example, `shift(start_index, 0, 1)` returns the neighbor to the upper right.
+    /// Offsets can be negative and can take any value in the range of `i32`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.shift(55, 0, 1), 66);
+    /// assert_eq!(hex.shift(45, 0, 1), 55);
+    /// assert_eq!(hex.shift(0, -1, 0), 9);
+    /// ```
+    pub fn shift(&self, start_index: usize, delta_q: i32, delta_r: i32) -> usize {
+        let (q, r) = self.axial(start_index);
+        self.locate(q as i64 + delta_q as i64, r as i64 + delta_r as i64)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 6 neighbors.
+    /// The indices are ordered counter-clockwise, starting from the neighbor to the right.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.neighbors6(0), vec![1, 10, 19, 9, 99, 90]);
+    /// ```
+    pub fn neighbors6(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors6_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 6 neighbors,
+    /// in the same order as [`neighbors6`](#method.neighbors6). Unlike `neighbors6`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.neighbors6_array(55), [56, 66, 65, 54, 45, 46]);
+    /// ```
+    pub fn neighbors6_array(&self, start_index: usize) -> [usize; 6] {
+        DIRECTIONS.map(|(dq, dr)| self.shift(start_index, dq, dr))
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to the hexagons at
+    /// distance `radius`, that is, the hex ring of radius `radius`, which has `6 * radius` cells.
+    /// The ring starts at the `radius`-th cell to the right and goes counter-clockwise.
+    /// For radius 0, the ring is the cell itself; for radius 1, this is the order of `neighbors6`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// assert_eq!(hex.ring(55, 0), vec![55]);
+    /// assert_eq!(hex.ring(55, 1), hex.neighbors6(55));
+    /// let ring = hex.ring(55, 2);
+    /// assert_eq!(ring.len(), 12);
+    /// assert_eq!(ring[0], 57);
+    /// ```
+    pub fn ring(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        if radius == 0 {
+            return vec![start_index % self.szu];
+        }
+        let (q0, r0) = self.axial(start_index);
+        let radius = radius as i64;
+        let (mut q, mut r) = (q0 as i64 + radius, r0 as i64);
+        let mut ring = std::vec::Vec::with_capacity(6 * radius as usize);
+        // Walk each side, starting with the side from the right corner to the upper right corner
+        for side in 0..6 {
+            let (dq, dr) = DIRECTIONS[(side + 2) % 6];
+            for _ in 0..radius {
+                ring.push(self.locate(q, r));
+                q += dq as i64;
+                r += dr as i64;
+            }
+        }
+        ring
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to the hexagons at a
+    /// distance between 1 and `radius`. The indices are ordered ring by ring, starting with the closest ring;
+    /// each ring is ordered as in [`ring`](#method.ring).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// let neighbors = hex.neighbors_radius(55, 2);
+    /// assert_eq!(neighbors.len(), 18);
+    /// assert_eq!(neighbors[..6], hex.neighbors6(55));
+    /// assert_eq!(neighbors[6..], hex.ring(55, 2));
+    /// ```
+    pub fn neighbors_radius(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        (1..=radius)
+            .flat_map(|k| self.ring(start_index, k))
+            .collect()
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 6 neighbors,
+    /// ordered as in [`neighbors6`](#method.neighbors6). Like `WrappingCoords2d::for_each8`, this function
+    /// walks the grid row by row without converting between indices and coordinates for each cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// hex.for_each6(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], hex.shift(this_cell_index, 1, 0));
+    ///     assert_eq!(neighbors[1], hex.shift(this_cell_index, 0, 1));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, hex.size());
+    /// ```
+    pub fn for_each6<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, &[usize; 6]),
+    {
+        let mut neighbors = [0; 6];
+        let mut this_cell_index = 0;
+        for y in 0..self.h32 {
+            let offsets = if y % 2 == 0 {
+                &EVEN_ROW_OFFSETS
+            } else {
+                &ODD_ROW_OFFSETS
+            };
+            // Each row rebuilds its shifts from its own offsets: x shifts in the form (x + w), and y shifts reduced
+            // to yw, so the inner loop only adds to and reduces usize values
+            let x_shifts =
+                offsets.map(|(dx, _)| WrappingCoords2d::modulo(dx, self.w32) as usize + self.wu);
+            let yw_shifts = offsets
+                .map(|(_, dy)| WrappingCoords2d::modulo(y + dy, self.h32) as usize * self.wu);
+            for x in 0..self.wu {
+                for (j, neighbor) in neighbors.iter_mut().enumerate() {
+                    *neighbor = yw_shifts[j] + (x_shifts[j] + x) % self.wu;
+                }
+                f(this_cell_index, &neighbors);
+                this_cell_index += 1;
+            }
+        }
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 6 neighbors,
+    /// ordered as in [`neighbors6`](#method.neighbors6).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingHex2d;
+    /// let hex = WrappingHex2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// hex.for_each_pair6(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index != neighbor_index);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 6 * hex.size());
+    /// ```
+    pub fn for_each_pair6<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each6(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_grids() {
+        assert!(matches!(
+            WrappingHex2d::new(4, 3),
+            Err(ErrorKind::DimensionsNotEven)
+        ));
+        assert!(matches!(
+            WrappingHex2d::new(-4, 3),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingHex2d::new(i32::MAX, 2),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        for &(w, h) in [(10, 10), (7, 4), (1, 2), (3, 2), (5, 12)].iter() {
+            let hex = WrappingHex2d::new(w, h).unwrap();
+            for index in 0..hex.size() {
+                let (x, y) = hex.coords(index);
+                assert_eq!(hex.index(x + w, y - h), index);
+                let (q, r) = hex.axial(index);
+                assert_eq!(hex.index_axial(q, r), index);
+                // The grid repeats along (width, 0) and (-height / 2, height)
+                assert_eq!(hex.shift(index, w, 0), index);
+                assert_eq!(hex.shift(index, -h / 2, h), index);
+                assert_eq!(
+                    hex.shift(index, i32::MAX, 0),
+                    hex.shift(index, i32::MAX % w, 0)
+                );
+                assert_eq!(
+                    hex.shift(index, i32::MAX, i32::MIN),
+                    hex.shift(hex.shift(index, i32::MAX, 0), 0, i32::MIN)
+                );
+                // Neighbors follow the offset tables of the row
+                let offsets = if y % 2 == 0 {
+                    EVEN_ROW_OFFSETS
+                } else {
+                    ODD_ROW_OFFSETS
+                };
+                let neighbors = hex.neighbors6_array(index);
+                for (j, &(dx, dy)) in offsets.iter().enumerate() {
+                    assert_eq!(neighbors[j], hex.index(x + dx, y + dy));
+                    // Adjacency is symmetric: the opposite direction leads back
+                    assert_eq!(hex.neighbors6_array(neighbors[j])[(j + 3) % 6], index);
+                }
+            }
+            let mut calls_counter = 0;
+            hex.for_each6(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &hex.neighbors6_array(this_cell_index));
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, hex.size());
+        }
+        // On a large grid, the rings of radius 1 to 4 hold distinct cells at the expected distance
+        let hex = WrappingHex2d::new(20, 20).unwrap();
+        let start = hex.index(7, 9);
+        let mut seen = vec![start];
+        for radius in 1..5 {
+            let ring = hex.ring(start, radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            for &cell in ring.iter() {
+                let (q0, r0) = hex.axial(start);
+                let (q, r) = hex.axial(cell);
+                let (dq, dr) = (q - q0, r - r0);
+                assert_eq!((dq.abs() + dr.abs() + (dq + dr).abs()) / 2, radius as i32);
+                assert!(!seen.contains(&cell));
+                seen.push(cell);
+            }
+        }
+        assert_eq!(hex.neighbors_radius(start, 4), seen[1..]);
+        // Invalid start indices wrap around the grid
+        let hex = WrappingHex2d::new(10, 10).unwrap();
+        for &start in [250, 1 << 31, usize::MAX - 5].iter() {
+            assert_eq!(hex.shift(start, 0, 0), start % 100);
+            assert_eq!(hex.shift(start, 3, -7), hex.shift(start % 100, 3, -7));
+            assert_eq!(hex.ring(start, 0), vec![start % 100]);
+            assert_eq!(hex.ring(start, 2), hex.ring(start % 100, 2));
+        }
+    }
+}
@@ -83,13 +83,49 @@
 //! assert_eq!(w2d.shift(0, 1, -1), 91);
 //! ```
 
-/// Represents errors in the construction of a 2D grid.
+mod coords1d;
+mod coords3d;
+mod coordsn;
+mod direction;
+mod generic;
+mod hex;
+mod linked;
+mod masked;
+mod tri;
+mod typed;
+
+pub use coords1d::{Rule1d, WrappingCoords1d};
+pub use coords3d::WrappingCoords3d;
+pub use coordsn::WrappingCoords;
+pub use direction::{Direction, Direction4};
+pub use generic::{GenericCoords2d, GridInt, WrappingCoords2d16, WrappingCoords2d64};
+pub use hex::WrappingHex2d;
+pub use linked::LinkedCoords2d;
+pub use masked::MaskedCoords2d;
+pub use tri::WrappingTri2d;
+pub use typed::{CellIndex, Coord};
+
+/// Represents errors in the construction of a grid or a rule.
 #[derive(Debug)]
 pub enum ErrorKind {
-    /// `width` or `height` less than 1.
+    /// `width`, `height`, or `depth` less than 1.
     DimensionsLessThan1,
-    /// The product of `width` and `height` exceeds `std::i32::MAX`.
+    /// The product of `width`, `height`, and `depth` exceeds `std::i32::MAX`; for [`GenericCoords2d`],
+    /// the product of `width` and `height` exceeds `usize::MAX`.
     DimensionsTooLarge,
+    /// `width` or `height` is smaller than the diameter of the requested neighborhood;
+    /// see [`WrappingCoords2d::new_with_radius`].
+    GridTooSmallForNeighborhood,
+    /// The length of a mask differs from the size of the grid; see [`MaskedCoords2d::new`].
+    MaskSizeMismatch,
+    /// An index is not in the range [0, size); see [`LinkedCoords2d::add_link`].
+    IndexOutOfRange,
+    /// A dimension of the grid is odd, but the lattice only wraps consistently if it is even;
+    /// see [`WrappingHex2d::new`] and [`WrappingTri2d::new`].
+    DimensionsNotEven,
+    /// The table of a rule does not match its number of states and radius, or the radius is too large;
+    /// see [`Rule1d::new`].
+    InvalidRule,
 }
 
 impl std::error::Error for ErrorKind {}
@@ -97,14 +133,261 @@ impl std::error::Error for ErrorKind {}
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self {
-            ErrorKind::DimensionsLessThan1 => write!(f, "width or height less than 1"),
-            ErrorKind::DimensionsTooLarge => write!(f, "the product of width and height exceeds std::i32::MAX = {}", std::i32::MAX),
+            ErrorKind::DimensionsLessThan1 => write!(f, "width, height, or depth less than 1"),
+            ErrorKind::DimensionsTooLarge => write!(
+                f,
+                "the product of the dimensions exceeds the maximum number of cells of the grid type"
+            ),
+            ErrorKind::GridTooSmallForNeighborhood => write!(
+                f,
+                "width or height smaller than the diameter of the neighborhood"
+            ),
+            ErrorKind::MaskSizeMismatch => {
+                write!(f, "mask length differs from the size of the grid")
+            }
+            ErrorKind::IndexOutOfRange => write!(f, "index outside of the grid"),
+            ErrorKind::DimensionsNotEven => {
+                write!(f, "the lattice requires an even dimension of the grid")
+            }
+            ErrorKind::InvalidRule => write!(
+                f,
+                "rule table length differs from states^(2 * radius + 1), it holds an invalid state, or the radius is too large"
+            ),
+        }
+    }
+}
+
+/// Offsets `(dx, dy)` of the 4-neighborhood, counter-clockwise, starting from the right neighbor.
+const OFFSETS4: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Offsets `(dx, dy)` of the 8-neighborhood, counter-clockwise, starting from the right neighbor.
+const OFFSETS8: [(i32, i32); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// Offsets `(dx, dy)` of the 16 second neighbors, counter-clockwise, starting from the second cell to the right.
+const OFFSETS16: [(i32, i32); 16] = [
+    (2, 0),
+    (2, 1),
+    (2, 2),
+    (1, 2),
+    (0, 2),
+    (-1, 2),
+    (-2, 2),
+    (-2, 1),
+    (-2, 0),
+    (-2, -1),
+    (-2, -2),
+    (-1, -2),
+    (0, -2),
+    (1, -2),
+    (2, -2),
+    (2, -1),
+];
+
+/// Offsets `(dx, dy)` of the 24 nearest neighbors: the 8-neighborhood followed by the 16 second neighbors.
+const OFFSETS24: [(i32, i32); 24] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, 0),
+    (2, 1),
+    (2, 2),
+    (1, 2),
+    (0, 2),
+    (-1, 2),
+    (-2, 2),
+    (-2, 1),
+    (-2, 0),
+    (-2, -1),
+    (-2, -2),
+    (-1, -2),
+    (0, -2),
+    (1, -2),
+    (2, -2),
+    (2, -1),
+];
+
+/// Returns the offsets `(dx, dy)` of the Moore neighborhood of radius `radius`, that is, every cell at a Chebyshev
+/// distance between 1 and `radius`. The offsets are ordered ring by ring, starting with the closest ring.
+/// Each ring at distance `k` is ordered counter-clockwise, starting from `(k, 0)`.
+fn moore_offsets(radius: u32) -> std::vec::Vec<(i32, i32)> {
+    // Offsets have to fit in i32
+    let r = i32::try_from(radius).unwrap_or(i32::MAX);
+    // A capacity that overflows usize could never be allocated; the vector grows as needed instead
+    let side = (r as usize).checked_mul(2).and_then(|d| d.checked_add(1));
+    let capacity = side
+        .and_then(|side| side.checked_mul(side))
+        .map_or(0, |cells| cells - 1);
+    let mut offsets = std::vec::Vec::with_capacity(capacity);
+    for k in 1..=r {
+        offsets.extend((0..k).map(|dy| (k, dy))); // Right side, going up
+        offsets.extend((-k + 1..=k).rev().map(|dx| (dx, k))); // Top side, going left
+        offsets.extend((-k + 1..=k).rev().map(|dy| (-k, dy))); // Left side, going down
+        offsets.extend((-k..k).map(|dx| (dx, -k))); // Bottom side, going right
+        offsets.extend((-k..0).map(|dy| (k, dy))); // Right side, going up
+    }
+    offsets
+}
+
+/// Returns the offsets `(dx, dy)` of the von Neumann neighborhood of radius `radius`, that is, every cell at a Manhattan
+/// distance between 1 and `radius`. The offsets are ordered ring by ring, starting with the closest ring.
+/// Each ring at distance `k` is ordered counter-clockwise, starting from `(k, 0)`.
+fn von_neumann_offsets(radius: u32) -> std::vec::Vec<(i32, i32)> {
+    let r = i32::try_from(radius).unwrap_or(i32::MAX); // Offsets have to fit in i32
+    let capacity = (r as usize)
+        .checked_mul(r as usize + 1)
+        .and_then(|cells| cells.checked_mul(2));
+    let mut offsets = std::vec::Vec::with_capacity(capacity.unwrap_or(0));
+    for k in 1..=r {
+        offsets.extend((0..k).map(|i| (k - i, i))); // Upper right side
+        offsets.extend((0..k).map(|i| (-i, k - i))); // Upper left side
+        offsets.extend((0..k).map(|i| (i - k, -i))); // Lower left side
+        offsets.extend((0..k).map(|i| (i, i - k))); // Lower right side
+    }
+    offsets
+}
+
+/// Returns the offsets `(dx, dy)` of every cell whose Euclidean distance to the center lies in the range
+/// [`inner_radius`, `outer_radius`], except the center itself, and whose components do not exceed `extent`.
+/// The offsets are ordered by distance; offsets at the same distance are ordered counter-clockwise, starting from the right.
+fn ring_offsets(
+    inner_radius: f64,
+    outer_radius: f64,
+    extent: (i32, i32),
+) -> std::vec::Vec<(i32, i32)> {
+    let mut offsets = std::vec::Vec::new();
+    if outer_radius.is_nan() || outer_radius < 0.0 {
+        return offsets;
+    }
+    let r = outer_radius.floor() as i32; // Saturates on huge radii
+    let (rx, ry) = (r.min(extent.0), r.min(extent.1));
+    let inner2 = if inner_radius > 0.0 {
+        inner_radius * inner_radius
+    } else {
+        0.0
+    };
+    let outer2 = outer_radius * outer_radius;
+    for dy in -ry..=ry {
+        for dx in -rx..=rx {
+            let d2 = (dx as i64 * dx as i64 + dy as i64 * dy as i64) as f64;
+            if d2 > 0.0 && inner2 <= d2 && d2 <= outer2 {
+                offsets.push((dx, dy));
+            }
         }
     }
+    offsets.sort_by(|&a, &b| {
+        squared_length(a)
+            .cmp(&squared_length(b))
+            .then_with(|| compare_angles((1, 0), a, b))
+    });
+    offsets
+}
+
+/// Returns the squared Euclidean length of `offset`.
+fn squared_length((dx, dy): (i32, i32)) -> i64 {
+    dx as i64 * dx as i64 + dy as i64 * dy as i64
+}
+
+/// Returns the Chebyshev length of `offset`, that is, the index of its ring in a Moore neighborhood.
+fn chebyshev_length((dx, dy): (i32, i32)) -> i64 {
+    (dx as i64).abs().max((dy as i64).abs())
+}
+
+/// Returns the Manhattan length of `offset`, that is, the index of its ring in a von Neumann neighborhood.
+fn manhattan_length((dx, dy): (i32, i32)) -> i64 {
+    (dx as i64).abs() + (dy as i64).abs()
+}
+
+/// Compares the angles of two non-zero offsets, measured counter-clockwise from the non-zero offset `start`,
+/// in the range [0, 2 pi). The comparison is exact because it uses integer arithmetic only.
+fn compare_angles(start: (i32, i32), a: (i32, i32), b: (i32, i32)) -> std::cmp::Ordering {
+    // Offsets in the half plane to the left of start, including start itself, come first
+    let half = |(dx, dy): (i32, i32)| {
+        let cross = start.0 as i64 * dy as i64 - start.1 as i64 * dx as i64;
+        let dot = start.0 as i64 * dx as i64 + start.1 as i64 * dy as i64;
+        if cross > 0 || (cross == 0 && dot > 0) {
+            0
+        } else {
+            1
+        }
+    };
+    half(a).cmp(&half(b)).then_with(|| {
+        // Within a half plane, b comes after a if the cross product a x b is positive
+        let cross = a.0 as i64 * b.1 as i64 - a.1 as i64 * b.0 as i64;
+        0.cmp(&cross)
+    })
 }
 
 /// Represents a 2D grid with wrapping.
-#[derive(Debug, PartialEq)]
+///
+/// # Small grids
+///
+/// Each neighbor function, such as `neighbors8` or `for_each16`, returns exactly one index per offset of its
+/// neighborhood, in the documented order, and that index is always the one returned by `shift` for the same offset.
+/// When `width` or `height` is smaller than the diameter of the neighborhood (3 for the 4- and 8-neighborhoods,
+/// 5 for the 16 and 24 neighborhoods), several offsets wrap onto the same cell, which may be the cell itself.
+/// For example, each of the 8 neighbors of the only cell in a `1x1` grid is the cell itself.
+/// Construct the grid with [`new_with_radius`](#method.new_with_radius) to rule out such grids.
+///
+/// # Boundaries
+///
+/// By default, both axes wrap and the grid is a torus. Construct the grid with
+/// [`new_with_boundaries`](#method.new_with_boundaries) to choose a different [`Boundary`] policy for each axis;
+/// for example, a cylinder wraps east-west and absorbs north-south. The policies apply to every function that moves
+/// across the grid, such as `index`, `shift`, and the neighbor functions:
+///
+/// - Functions that return an `Option`, such as `index_checked`, `shift_checked` and `for_each8_checked`,
+///   return `None` for cells beyond an edge with `Boundary::Absorb`.
+/// - Functions that return vectors or slices, such as `neighbors8` or `for_each_stencil`, omit such cells.
+/// - Functions that return a single index or a fixed-size array, such as `index`, `shift`, `neighbors8_array` or
+///   `for_each8`, panic instead.
+///
+/// # Topologies
+///
+/// Construct the grid with [`new_with_topology`](#method.new_with_topology) to glue the edges of the grid with a twist,
+/// as in a Möbius strip, a Klein bottle, or a real projective plane; see [`Topology`]. A move across a twisted edge
+/// mirrors the other coordinate, so a move `(delta_x, delta_y)` goes along the x axis first, and then along the
+/// y axis in the mirrored direction. [`shift_oriented`](#method.shift_oriented) reports these flips, so that agents
+/// can correct their headings. All functions follow the same rule; in particular, `index(x, y)` is the cell
+/// reached by moving from `(0, 0)` by `(x, y)`, or from `(min_x, min_y)` by `(x - min_x, y - min_y)` on grids
+/// with [bounds](#bounds).
+///
+/// The same constructor provides the helical and shifted periodic boundary conditions of lattice Monte Carlo codes,
+/// where crossing an edge moves the other coordinate instead of mirroring it.
+///
+/// # Neighbor order
+///
+/// By default, neighbor functions order each ring of neighbors counter-clockwise, starting from the cell to the right,
+/// with y increasing upwards; the documentation of each function describes this order. Construct the grid with
+/// [`new_with_order`](#method.new_with_order), or set the order of any grid with [`with_order`](#method.with_order),
+/// to follow the convention of another tool instead, such as clockwise from north with y increasing downwards;
+/// see [`NeighborOrder`]. The convention applies to the `neighbors*`, `neighbors_*` and `for_each*` functions,
+/// except the stencil functions, which keep the order of the stencil offsets. It does not change coordinates:
+/// `index`, `coords` and `shift` work as usual. [`step`](#method.step) and [`direction_between`](#method.direction_between)
+/// follow the y axis of the convention, so that `Direction::North` points to the top of the screen.
+///
+/// # Bounds
+///
+/// By default, x coordinates go from 0 to `width - 1`, and y coordinates go from 0 to `height - 1`. Construct the grid
+/// with [`from_bounds`](#method.from_bounds), or move the coordinates of any grid with
+/// [`with_min_coords`](#method.with_min_coords), to use the coordinates of a model instead, such as a NetLogo world
+/// with the origin at the center. Functions that take or return coordinates, such as `index`, `coords`, and the `*xy`
+/// functions, use the coordinates of the model, and the cell at `(min_x, min_y)` has index 0. Functions that take
+/// offsets, such as `shift` and the neighbor functions, work as usual, because offsets do not depend on the origin.
+#[derive(Clone, Debug, PartialEq)]
 pub struct WrappingCoords2d {
     /// Width of the grid; it has to be larger than 0.
     w32: i32,
@@ -116,14 +399,32 @@ pub struct WrappingCoords2d {
     wu: usize,
     /// Total number of cells in the grid.
     szu: usize,
+    /// Boundary policy of the x axis.
+    x_boundary: Boundary,
+    /// Boundary policy of the y axis.
+    y_boundary: Boundary,
+    /// Twists of the edges that wrap.
+    topology: Topology,
+    /// Order of the neighbors returned by the neighbor functions.
+    order: NeighborOrder,
+    /// Offsets of the built-in neighborhoods, arranged in `order`.
+    offsets: OrderedOffsets,
+    /// Smallest x coordinate of the grid.
+    min_x: i32,
+    /// Smallest y coordinate of the grid.
+    min_y: i32,
 }
 
+/// Message of the panics caused by cells beyond an edge with `Boundary::Absorb`.
+const ABSORBED: &str = "the cell lies beyond an edge with Boundary::Absorb";
+
 impl WrappingCoords2d {
     /// Constructs a new WrappingCoords2d object.
     ///
     /// # Errors
     ///
     /// Both `width` and `height` must be larger than 0. Also, their product must be smaller than `std::i32::MAX = 2147483647`.
+    /// For larger grids, see [`WrappingCoords2d64`].
     /// Generally speaking, [`i32` is the fastest] integer type, even on 64-bit systems. `i32` is sufficient for a wide range
     /// of agent-based models. You will need to modify the data type to accommodate larger landscapes.
     ///
@@ -141,6 +442,13 @@ impl WrappingCoords2d {
                     sz32: s,
                     wu: width as usize,
                     szu: s as usize,
+                    x_boundary: Boundary::Wrap,
+                    y_boundary: Boundary::Wrap,
+                    topology: Topology::Torus,
+                    order: NeighborOrder::default(),
+                    offsets: OrderedOffsets::new(NeighborOrder::default()),
+                    min_x: 0,
+                    min_y: 0,
                 }),
                 None => Err(ErrorKind::DimensionsTooLarge),
             }
@@ -148,6 +456,408 @@ impl WrappingCoords2d {
             Err(ErrorKind::DimensionsLessThan1)
         }
     }
+    /// Constructs a new WrappingCoords2d object whose neighborhoods of radius `radius` contain distinct cells.
+    /// Radius 1 corresponds to the 4- and 8-neighborhoods, and radius 2 corresponds to the 16 and 24 neighborhoods.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by [`new`](#method.new), this function returns
+    /// `ErrorKind::GridTooSmallForNeighborhood` when `width` or `height` is smaller than `2 * radius + 1`.
+    /// On such grids, neighbor functions return repeated indices; see [Small grids](#small-grids).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingCoords2d};
+    /// assert!(WrappingCoords2d::new_with_radius(3, 3, 1).is_ok());
+    /// assert!(matches!(
+    ///     WrappingCoords2d::new_with_radius(3, 3, 2),
+    ///     Err(ErrorKind::GridTooSmallForNeighborhood)
+    /// ));
+    /// assert!(matches!(
+    ///     WrappingCoords2d::new_with_radius(0, 3, 1),
+    ///     Err(ErrorKind::DimensionsLessThan1)
+    /// ));
+    /// ```
+    pub fn new_with_radius(
+        width: i32,
+        height: i32,
+        radius: u32,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        let w2d = WrappingCoords2d::new(width, height)?;
+        let diameter = 2 * radius as i64 + 1;
+        if (width as i64) < diameter || (height as i64) < diameter {
+            Err(ErrorKind::GridTooSmallForNeighborhood)
+        } else {
+            Ok(w2d)
+        }
+    }
+    /// Constructs a new WrappingCoords2d object with the boundary policy `x_boundary` along the x axis,
+    /// and `y_boundary` along the y axis. See [Boundaries](#boundaries).
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`new`](#method.new).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// // A cylinder that wraps east-west, with hard walls to the north and south:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb).unwrap();
+    /// assert_eq!(w2d.shift_checked(95, 5, 0), Some(90));
+    /// assert_eq!(w2d.shift_checked(95, 0, 1), None);
+    /// // The northern neighbor of (5, 9) is missing:
+    /// assert_eq!(w2d.neighbors4(95), vec![96, 94, 85]);
+    /// ```
+    pub fn new_with_boundaries(
+        width: i32,
+        height: i32,
+        x_boundary: Boundary,
+        y_boundary: Boundary,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        let mut w2d = WrappingCoords2d::new(width, height)?;
+        w2d.x_boundary = x_boundary;
+        w2d.y_boundary = y_boundary;
+        Ok(w2d)
+    }
+    /// Constructs a new WrappingCoords2d object whose edges are glued according to `topology`.
+    /// See [Topologies](#topologies).
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`new`](#method.new).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, Topology, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::KleinBottle).unwrap();
+    /// // Crossing the right edge of a Klein bottle mirrors the y coordinate:
+    /// assert_eq!(w2d.shift(29, 1, 0), 70);
+    /// assert_eq!(w2d.shift(99, 1, 0), 0);
+    /// assert_eq!(w2d.index(10, 2), 70);
+    /// // A Möbius strip absorbs moves across its top and bottom edges:
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::MobiusStrip).unwrap();
+    /// assert_eq!(w2d.y_boundary(), Boundary::Absorb);
+    /// assert_eq!(w2d.shift_checked(95, 0, 1), None);
+    /// // Helical boundaries continue on the next row:
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::Helical).unwrap();
+    /// assert_eq!(w2d.shift(9, 1, 0), 10);
+    /// assert_eq!(w2d.shift(99, 1, 0), 0);
+    /// // A shifted torus moves the x coordinate when crossing the top edge:
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::ShiftedTorus(3)).unwrap();
+    /// assert_eq!(w2d.shift(95, 0, 1), 8);
+    /// assert_eq!(w2d.shift(8, 0, -1), 95);
+    /// ```
+    pub fn new_with_topology(
+        width: i32,
+        height: i32,
+        topology: Topology,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        let mut w2d = WrappingCoords2d::new(width, height)?;
+        if topology == Topology::MobiusStrip {
+            w2d.y_boundary = Boundary::Absorb;
+        }
+        w2d.topology = topology;
+        Ok(w2d)
+    }
+    /// Constructs a new WrappingCoords2d object whose neighbor functions follow the convention `order`.
+    /// See [Neighbor order](#neighbor-order).
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`new`](#method.new).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, NeighborOrder, Rotation, WrappingCoords2d, YAxis};
+    /// // Clockwise from north, with rows going down the screen, as in image libraries:
+    /// let order = NeighborOrder {
+    ///     start: Direction::North,
+    ///     rotation: Rotation::Clockwise,
+    ///     y_axis: YAxis::Down,
+    /// };
+    /// let w2d = WrappingCoords2d::new_with_order(10, 10, order).unwrap();
+    /// // Here are the 4 neighbors of the cell at (5, 5): above, right, below, and left, as seen on the screen:
+    /// assert_eq!(w2d.neighbors4(55), vec![45, 56, 65, 54]);
+    /// assert_eq!(w2d.neighbors8(55), vec![45, 46, 56, 66, 65, 64, 54, 44]);
+    /// ```
+    pub fn new_with_order(
+        width: i32,
+        height: i32,
+        order: NeighborOrder,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        Ok(WrappingCoords2d::new(width, height)?.with_order(order))
+    }
+    /// Returns this grid with the neighbor order `order`, keeping its dimensions, boundaries and topology.
+    /// See [Neighbor order](#neighbor-order).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, Direction, NeighborOrder, Rotation, WrappingCoords2d};
+    /// let order = NeighborOrder {
+    ///     start: Direction::North,
+    ///     rotation: Rotation::Clockwise,
+    ///     ..NeighborOrder::default()
+    /// };
+    /// // A cylinder that wraps east-west, with its neighbors clockwise from north:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb)
+    ///     .unwrap()
+    ///     .with_order(order);
+    /// assert_eq!(w2d.order(), order);
+    /// assert_eq!(w2d.neighbors4(55), vec![65, 56, 45, 54]);
+    /// // The northern neighbor of (5, 9) is missing:
+    /// assert_eq!(w2d.neighbors4(95), vec![96, 85, 94]);
+    /// ```
+    pub fn with_order(mut self, order: NeighborOrder) -> WrappingCoords2d {
+        self.order = order;
+        self.offsets = OrderedOffsets::new(order);
+        self
+    }
+    /// Constructs a new WrappingCoords2d object whose x coordinates go from `min_x` to `max_x`, and whose y coordinates
+    /// go from `min_y` to `max_y`, both inclusive, as the patches of a NetLogo world. See [Bounds](#bounds).
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::DimensionsLessThan1` if `max_x < min_x` or `max_y < min_y`.
+    /// Otherwise, it returns the same errors as [`new`](#method.new), with a width of `max_x - min_x + 1`
+    /// and a height of `max_y - min_y + 1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// // The default NetLogo world, with the origin at the center:
+    /// let w2d = WrappingCoords2d::from_bounds(-16, 16, -16, 16).unwrap();
+    /// assert_eq!(w2d.width(), 33);
+    /// assert_eq!(w2d.size(), 1089);
+    /// // The lower left patch is the first cell of the 1D container:
+    /// assert_eq!(w2d.index(-16, -16), 0);
+    /// assert_eq!(w2d.coords(0), (-16, -16));
+    /// assert_eq!(w2d.coords(w2d.index(0, 0)), (0, 0));
+    /// // Coordinates wrap around the bounds:
+    /// assert_eq!(w2d.index(17, 0), w2d.index(-16, 0));
+    /// assert_eq!(w2d.coords(w2d.shift(w2d.index(16, 16), 1, 1)), (-16, -16));
+    /// ```
+    pub fn from_bounds(
+        min_x: i32,
+        max_x: i32,
+        min_y: i32,
+        max_y: i32,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        let width = max_x as i64 - min_x as i64 + 1;
+        let height = max_y as i64 - min_y as i64 + 1;
+        if width < 1 || height < 1 {
+            return Err(ErrorKind::DimensionsLessThan1);
+        }
+        if width > i32::MAX as i64 || height > i32::MAX as i64 {
+            return Err(ErrorKind::DimensionsTooLarge);
+        }
+        WrappingCoords2d::new(width as i32, height as i32)?.with_min_coords(min_x, min_y)
+    }
+    /// Returns this grid with its lower left cell at `(min_x, min_y)`, keeping its dimensions, boundaries, topology and
+    /// neighbor order. Use this function to give the coordinates of a model to any grid; see [Bounds](#bounds).
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::DimensionsTooLarge` if the largest x or y coordinate of the grid,
+    /// `min_x + width - 1` or `min_y + height - 1`, exceeds `std::i32::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, ErrorKind, WrappingCoords2d};
+    /// // A NetLogo world that wraps horizontally, but not vertically:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(33, 33, Boundary::Wrap, Boundary::Absorb)
+    ///     .unwrap()
+    ///     .with_min_coords(-16, -16)
+    ///     .unwrap();
+    /// assert_eq!((w2d.max_x(), w2d.max_y()), (16, 16));
+    /// assert_eq!(w2d.index_checked(17, 0), w2d.index_checked(-16, 0));
+    /// assert_eq!(w2d.index_checked(0, 17), None);
+    /// assert!(matches!(
+    ///     WrappingCoords2d::new(10, 10).unwrap().with_min_coords(i32::MAX, 0),
+    ///     Err(ErrorKind::DimensionsTooLarge)
+    /// ));
+    /// ```
+    pub fn with_min_coords(
+        mut self,
+        min_x: i32,
+        min_y: i32,
+    ) -> Result<WrappingCoords2d, ErrorKind> {
+        let max_x = min_x as i64 + self.w32 as i64 - 1;
+        let max_y = min_y as i64 + self.h32 as i64 - 1;
+        if max_x > i32::MAX as i64 || max_y > i32::MAX as i64 {
+            return Err(ErrorKind::DimensionsTooLarge);
+        }
+        self.min_x = min_x;
+        self.min_y = min_y;
+        Ok(self)
+    }
+    /// Returns the topology of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Topology, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.topology(), Topology::Torus);
+    /// ```
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+    /// Returns the boundary policy of the x axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.x_boundary(), Boundary::Wrap);
+    /// ```
+    pub fn x_boundary(&self) -> Boundary {
+        self.x_boundary
+    }
+    /// Returns the boundary policy of the y axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Clamp).unwrap();
+    /// assert_eq!(w2d.y_boundary(), Boundary::Clamp);
+    /// ```
+    pub fn y_boundary(&self) -> Boundary {
+        self.y_boundary
+    }
+    /// Returns the order of the neighbors returned by the neighbor functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Direction, NeighborOrder, Rotation, WrappingCoords2d, YAxis};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.order().start, Direction::East);
+    /// assert_eq!(w2d.order().rotation, Rotation::CounterClockwise);
+    /// assert_eq!(w2d.order().y_axis, YAxis::Up);
+    /// ```
+    pub fn order(&self) -> NeighborOrder {
+        self.order
+    }
+    /// Returns the smallest x coordinate of the grid; see [Bounds](#bounds).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::from_bounds(-16, 16, -10, 5).unwrap();
+    /// assert_eq!(w2d.min_x(), -16);
+    /// assert_eq!(WrappingCoords2d::new(10, 10).unwrap().min_x(), 0);
+    /// ```
+    pub fn min_x(&self) -> i32 {
+        self.min_x
+    }
+    /// Returns the largest x coordinate of the grid; see [Bounds](#bounds).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::from_bounds(-16, 16, -10, 5).unwrap();
+    /// assert_eq!(w2d.max_x(), 16);
+    /// assert_eq!(WrappingCoords2d::new(10, 10).unwrap().max_x(), 9);
+    /// ```
+    pub fn max_x(&self) -> i32 {
+        self.min_x + (self.w32 - 1)
+    }
+    /// Returns the smallest y coordinate of the grid; see [Bounds](#bounds).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::from_bounds(-16, 16, -10, 5).unwrap();
+    /// assert_eq!(w2d.min_y(), -10);
+    /// ```
+    pub fn min_y(&self) -> i32 {
+        self.min_y
+    }
+    /// Returns the largest y coordinate of the grid; see [Bounds](#bounds).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::from_bounds(-16, 16, -10, 5).unwrap();
+    /// assert_eq!(w2d.max_y(), 5);
+    /// ```
+    pub fn max_y(&self) -> i32 {
+        self.min_y + (self.h32 - 1)
+    }
+    /// Returns `true` if both axes wrap without twisting. Such grids are tori, and they use the fast paths of this module.
+    fn is_torus(&self) -> bool {
+        self.topology == Topology::Torus
+            && self.x_boundary == Boundary::Wrap
+            && self.y_boundary == Boundary::Wrap
+    }
+    /// Returns `true` if an axis absorbs the cells beyond its edges.
+    fn absorbs(&self) -> bool {
+        self.x_boundary == Boundary::Absorb || self.y_boundary == Boundary::Absorb
+    }
+    /// Moves from the cell at `(x, y)`, in the range of the grid, by `delta_x` along the x axis first,
+    /// and then by `delta_y` along the y axis, according to the topology and the boundary policies.
+    /// Returns the index of the new cell and the flips caused by twisted edges,
+    /// or `None` if the new cell lies beyond an edge with `Boundary::Absorb`.
+    fn walk(&self, x: i32, y: i32, delta_x: i128, delta_y: i128) -> Option<(usize, Flip)> {
+        let mut flip = Flip::default();
+        let (mut y, mut delta_y) = (y as i128, delta_y);
+        let new_x = x as i128 + delta_x;
+        let mut x = self.x_boundary.resolve(new_x, self.w32)?;
+        if self.topology.twists_x() && new_x.div_euclid(self.w32 as i128) % 2 != 0 {
+            // An odd number of crossings of the x edge mirrors the y axis
+            y = self.h32 as i128 - 1 - y;
+            delta_y = -delta_y;
+            flip.y = true;
+        }
+        if self.topology == Topology::Helical {
+            // Each crossing of the x edge moves to the next or previous row
+            y += new_x.div_euclid(self.w32 as i128);
+        }
+        let new_y = y + delta_y;
+        let y = self.y_boundary.resolve(new_y, self.h32)?;
+        if self.topology.twists_y() && new_y.div_euclid(self.h32 as i128) % 2 != 0 {
+            x = self.w32 - 1 - x;
+            flip.x = true;
+        }
+        if let Topology::ShiftedTorus(shift) = self.topology {
+            // Each crossing of the y edge shifts the x coordinate
+            let shifted_x = x as i128 + new_y.div_euclid(self.h32 as i128) * shift as i128;
+            x = shifted_x.rem_euclid(self.w32 as i128) as i32;
+        }
+        Some(((y * self.w32 + x) as usize, flip))
+    }
+    /// Returns `offsets`, a list of neighborhood rings given by `ring`, with each ring arranged in the neighbor order of the grid.
+    fn arrange(
+        &self,
+        mut offsets: std::vec::Vec<(i32, i32)>,
+        ring: fn((i32, i32)) -> i64,
+    ) -> std::vec::Vec<(i32, i32)> {
+        self.order.arrange(&mut offsets, ring);
+        offsets
+    }
+    /// Returns `radius`, reduced to `width + height`. Rings beyond that distance only repeat the cells of closer rings,
+    /// or lie beyond an edge, whatever the boundaries and the topology of the grid.
+    fn reach(&self, radius: u32) -> u32 {
+        let limit = (self.w32 as i64 + self.h32 as i64).min(i32::MAX as i64);
+        radius.min(limit as u32)
+    }
+    /// Returns the index of the cell reached by `walk`, or `None` if the cell is absorbed.
+    fn locate(&self, x: i32, y: i32, delta_x: i128, delta_y: i128) -> Option<usize> {
+        self.walk(x, y, delta_x, delta_y).map(|(index, _)| index)
+    }
     /// Returns the width of the grid.
     ///
     /// # Examples
@@ -214,8 +924,24 @@ impl WrappingCoords2d {
         }
         res
     }
+    /// Returns `(lhs + delta)` modulo `rhs` for a `lhs` in the range [0, rhs), without overflowing `i32`.
+    /// `delta` can be negative.
+    fn wrapping_add(lhs: i32, delta: i32, rhs: i32) -> i32 {
+        let d = WrappingCoords2d::modulo(delta, rhs); // Positive number
+        let room = rhs - lhs; // Distance to the upper limit; always positive
+        if d >= room {
+            d - room
+        } else {
+            lhs + d
+        }
+    }
     /// Returns an index into the grid based on x and y coordinates.
     ///
+    /// # Panics
+    ///
+    /// This function panics if `(x, y)` lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`index_checked`](#method.index_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -241,14 +967,81 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.index(-1, -1), 99);
     /// assert_eq!(w2d.index(0, -1), 90);
     /// assert_eq!(w2d.index(1, -1), 91);
+    /// // Coordinates can take any value in the range of `i32`:
+    /// assert_eq!(w2d.index(i32::MAX, i32::MIN), 27);
     /// ```
     pub fn index(&self, x: i32, y: i32) -> usize {
+        if !self.is_torus() {
+            return self.index_checked(x, y).expect(ABSORBED);
+        }
+        let (x, y) = if self.min_x != 0 || self.min_y != 0 {
+            // The offsets from the lower left cell fit in i64; reduced, they fit in i32
+            let dx = (x as i64 - self.min_x as i64).rem_euclid(self.w32 as i64);
+            let dy = (y as i64 - self.min_y as i64).rem_euclid(self.h32 as i64);
+            (dx as i32, dy as i32)
+        } else {
+            (x, y)
+        };
         let mx = WrappingCoords2d::modulo(x, self.w32);
-        let myw = WrappingCoords2d::modulo(y * self.w32, self.sz32);
+        let myw = WrappingCoords2d::modulo(y, self.h32) * self.w32; // Smaller than size; no overflow
         (myw + mx) as usize
     }
+    /// Returns an index into the grid based on `x` and `y` coordinates,
+    /// or `None` if `(x, y)` lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Absorb, Boundary::Reflect).unwrap();
+    /// assert_eq!(w2d.index_checked(5, 9), Some(95));
+    /// assert_eq!(w2d.index_checked(10, 9), None);
+    /// // The row above the top row mirrors the row below it:
+    /// assert_eq!(w2d.index_checked(5, 10), Some(85));
+    /// ```
+    pub fn index_checked(&self, x: i32, y: i32) -> Option<usize> {
+        self.locate(
+            0,
+            0,
+            x as i128 - self.min_x as i128,
+            y as i128 - self.min_y as i128,
+        )
+    }
+    /// Returns an index into the grid based on `i64` x and y coordinates.
+    /// Use this function when agents accumulate coordinates beyond the range of `i32`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `(x, y)` lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.index64(5, 9), 95);
+    /// assert_eq!(w2d.index64(-1, -1), 99);
+    /// assert_eq!(w2d.index64(i64::MAX, i64::MIN), 27);
+    /// ```
+    pub fn index64(&self, x: i64, y: i64) -> usize {
+        let (x, y) = (
+            x as i128 - self.min_x as i128,
+            y as i128 - self.min_y as i128,
+        );
+        if !self.is_torus() {
+            return self.locate(0, 0, x, y).expect(ABSORBED);
+        }
+        let mx = x.rem_euclid(self.w32 as i128);
+        let my = y.rem_euclid(self.h32 as i128);
+        (my * self.w32 as i128 + mx) as usize
+    }
     /// Returns `x` and `y` coordinates based on an `index` into the 1D container.
     ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
     /// # Examples
     ///
     /// ```
@@ -261,9 +1054,40 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.coords(11), (1, 1));
     /// assert_eq!(w2d.coords(90), (0, 9));
     /// assert_eq!(w2d.coords(91), (1, 9));
+    /// // Grids do not need to be square:
+    /// let w2d = WrappingCoords2d::new(21, 2).unwrap();
+    /// assert_eq!(w2d.coords(20), (20, 0));
+    /// assert_eq!(w2d.coords(21), (0, 1));
+    /// assert_eq!(w2d.coords(41), (20, 1));
+    /// ```
     pub fn coords(&self, index: usize) -> (i32, i32) {
-        let idx32 = index as i32; // Always positive
-        (idx32 % self.w32, idx32 / self.h32)
+        let (x, y) = self.grid_coords(index);
+        (x + self.min_x, y + self.min_y)
+    }
+    /// Returns the coordinates of the cell given by `index`, counted from the lower left corner of the grid,
+    /// regardless of the bounds given to [`from_bounds`](#method.from_bounds).
+    fn grid_coords(&self, index: usize) -> (i32, i32) {
+        let idx32 = (index % self.szu) as i32; // Reduced, so that invalid indices stay in the grid
+        (idx32 % self.w32, idx32 / self.w32)
+    }
+    /// Returns `x` and `y` coordinates based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(21, 2).unwrap();
+    /// assert_eq!(w2d.coords_checked(0), Some((0, 0)));
+    /// assert_eq!(w2d.coords_checked(41), Some((20, 1)));
+    /// assert_eq!(w2d.coords_checked(42), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<(i32, i32)> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
     }
     /// Returns a new index into the grid based on a starting index `start_index`, an x offset, and a y offset.
     /// `delta_x` and `delta_y` can be negative.
@@ -272,6 +1096,11 @@ impl WrappingCoords2d {
     ///
     /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
     ///
+    /// # Panics
+    ///
+    /// This function panics if the new cell lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`shift_checked`](#method.shift_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -295,16 +1124,125 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.shift(0, -1, -1), 99);
     /// assert_eq!(w2d.shift(0, 0, -1), 90);
     /// assert_eq!(w2d.shift(0, 1, -1), 91);
+    /// // Offsets can take any value in the range of `i32`:
+    /// assert_eq!(w2d.shift(0, i32::MAX, i32::MIN), 27);
     /// ```
     pub fn shift(&self, start_index: usize, delta_x: i32, delta_y: i32) -> usize {
-        // Note: -11 % 10 = -1
-        let index = start_index as i32;
+        if !self.is_torus() {
+            return self
+                .shift_checked(start_index, delta_x, delta_y)
+                .expect(ABSORBED);
+        }
+        let index = (start_index % self.szu) as i32; // Reduced, so that invalid indices stay in the grid
         let x = index % self.w32; // Always positive
-        let new_x = WrappingCoords2d::modulo(x + delta_x, self.w32); // Positive number
-        let yw = index - x; // yw: The y coordinate times the width; always positive
-        let new_yw = WrappingCoords2d::modulo(yw + delta_y * self.w32, self.sz32); // Positive number
+        let y = index / self.w32; // Always positive
+        let new_x = WrappingCoords2d::wrapping_add(x, delta_x, self.w32); // Positive number
+        let new_yw = WrappingCoords2d::wrapping_add(y, delta_y, self.h32) * self.w32; // Positive number
         (new_yw + new_x) as usize
     }
+    /// Returns a new index into the grid based on a starting index `start_index`, and `i64` x and y offsets.
+    /// `delta_x` and `delta_y` can be negative.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the new cell lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.shift64(95, 1, 1), 6);
+    /// assert_eq!(w2d.shift64(0, -1, -1), 99);
+    /// assert_eq!(w2d.shift64(0, i64::MAX, i64::MIN), 27);
+    /// ```
+    pub fn shift64(&self, start_index: usize, delta_x: i64, delta_y: i64) -> usize {
+        if !self.is_torus() {
+            let (x, y) = self.grid_coords(start_index);
+            return self
+                .locate(x, y, delta_x.into(), delta_y.into())
+                .expect(ABSORBED);
+        }
+        // The reduced offsets fit in `i32` because they are smaller than the width and height
+        let dx = delta_x.rem_euclid(self.w32 as i64) as i32;
+        let dy = delta_y.rem_euclid(self.h32 as i64) as i32;
+        self.shift(start_index, dx, dy)
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, an x offset, and a y offset,
+    /// or `None` if the new cell lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Clamp, Boundary::Absorb).unwrap();
+    /// // Moves along the x axis stop at the edges:
+    /// assert_eq!(w2d.shift_checked(95, 100, 0), Some(99));
+    /// assert_eq!(w2d.shift_checked(95, 0, -9), Some(5));
+    /// assert_eq!(w2d.shift_checked(95, 0, 1), None);
+    /// ```
+    pub fn shift_checked(&self, start_index: usize, delta_x: i32, delta_y: i32) -> Option<usize> {
+        if self.is_torus() {
+            return Some(self.shift(start_index, delta_x, delta_y));
+        }
+        let (x, y) = self.grid_coords(start_index);
+        self.locate(x, y, delta_x.into(), delta_y.into())
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, an x offset, and a y offset,
+    /// together with the flips caused by crossing twisted edges. See [Topologies](#topologies).
+    ///
+    /// An agent that moves to the new cell keeps its orientation relative to the surface, so its heading,
+    /// expressed in grid coordinates, has to be mirrored with [`Flip::apply`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the new cell lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`shift_oriented_checked`](#method.shift_oriented_checked) on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Flip, Topology, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::KleinBottle).unwrap();
+    /// // An agent at (9, 2) heading up and to the right crosses the right edge:
+    /// let (index, flip) = w2d.shift_oriented(29, 1, 0);
+    /// assert_eq!(index, 70);
+    /// assert_eq!(flip, Flip { x: false, y: true });
+    /// // On the other side, the agent heads down and to the right:
+    /// assert_eq!(flip.apply((1, 1)), (1, -1));
+    /// ```
+    pub fn shift_oriented(&self, start_index: usize, delta_x: i32, delta_y: i32) -> (usize, Flip) {
+        self.shift_oriented_checked(start_index, delta_x, delta_y)
+            .expect(ABSORBED)
+    }
+    /// Returns the same index and flips as [`shift_oriented`](#method.shift_oriented),
+    /// or `None` if the new cell lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Flip, Topology, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::MobiusStrip).unwrap();
+    /// assert_eq!(w2d.shift_oriented_checked(0, -1, 0), Some((99, Flip { x: false, y: true })));
+    /// assert_eq!(w2d.shift_oriented_checked(0, 0, -1), None);
+    /// ```
+    pub fn shift_oriented_checked(
+        &self,
+        start_index: usize,
+        delta_x: i32,
+        delta_y: i32,
+    ) -> Option<(usize, Flip)> {
+        let (x, y) = self.grid_coords(start_index);
+        self.walk(x, y, delta_x.into(), delta_y.into())
+    }
     /// This function takes the cell given by `start_index` and returns a vector of the indices to its 4 neighbors,
     /// the so-called von Neumann neighborhood or 4-neighborhood. The indices are ordered in 2D, counter-clockwise,
     /// starting from the neighbor to the right.
@@ -324,22 +1262,54 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.neighbors4(0), vec![1, 10, 9, 90]);
     /// ```
     pub fn neighbors4(&self, start_index: usize) -> std::vec::Vec<usize> {
-        // Note: -11 % 10 = -1
-        let idx = start_index as i32;
-        let x = idx % self.w32; // Always positive
-        let yw = idx - x; // yw: The y coordinate times the width; always positive
-        let mut result32 = vec![x; 4];
-        result32[0] = (x + 1) % self.w32 + yw; // Neighbor to the right; modulo is always positive
-        result32[1] = (idx + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[2] = WrappingCoords2d::modulo(x - 1, self.w32) + yw; // Neighbor to the left
-        result32[3] = WrappingCoords2d::modulo(idx - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32.into_iter().map(|index| index as usize).collect()
+        if self.absorbs() {
+            return self.neighbors_offsets(start_index, &self.offsets.n4);
+        }
+        self.neighbors4_array(start_index).to_vec()
     }
-    /// This function takes the cell given by `(start_x, start_y)` and returns a vector of the indices to its 4 neighbors,
-    /// the so-called von Neumann neighborhood or 4-neighborhood. The indices are ordered in 2D, counter-clockwise,
-    /// starting from the neighbor to the right.
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 4 neighbors,
+    /// in the same order as [`neighbors4`](#method.neighbors4). Unlike `neighbors4`, this function does not allocate memory.
     ///
-    /// # Examples
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a neighbor lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`neighbors4`](#method.neighbors4) on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors4_array(95), [96, 5, 94, 85]);
+    /// assert_eq!(w2d.neighbors4_array(0), [1, 10, 9, 90]);
+    /// ```
+    pub fn neighbors4_array(&self, start_index: usize) -> [usize; 4] {
+        if !self.is_torus() || !self.order.is_default() {
+            return self
+                .offsets
+                .n4
+                .map(|(dx, dy)| self.shift(start_index, dx, dy));
+        }
+        let idx = (start_index % self.szu) as i32; // Reduced, so that invalid indices stay in the grid
+        let x = idx % self.w32; // Always positive
+        let y = idx / self.w32; // Always positive
+        let yw = idx - x; // yw: The y coordinate times the width; always positive
+        let xr1 = WrappingCoords2d::wrapping_add(x, 1, self.w32); // x coordinate of the column to the right
+        let xl1 = WrappingCoords2d::wrapping_add(x, -1, self.w32); // x coordinate of the column to the left
+        let ywa1 = WrappingCoords2d::wrapping_add(y, 1, self.h32) * self.w32; // Row above
+        let ywb1 = WrappingCoords2d::wrapping_add(y, -1, self.h32) * self.w32; // Row below
+        let result32 = [xr1 + yw, x + ywa1, xl1 + yw, x + ywb1];
+        result32.map(|index| index as usize)
+    }
+    /// This function takes the cell given by `(start_x, start_y)` and returns a vector of the indices to its 4 neighbors,
+    /// the so-called von Neumann neighborhood or 4-neighborhood. The indices are ordered in 2D, counter-clockwise,
+    /// starting from the neighbor to the right.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use wrapping_coords2d::WrappingCoords2d;
@@ -352,34 +1322,59 @@ impl WrappingCoords2d {
     pub fn neighbors4xy(&self, start_x: i32, start_y: i32) -> std::vec::Vec<usize> {
         self.neighbors4(self.index(start_x, start_y))
     }
+    /// Converts `offsets` into the `x_shifts` and `yw_shifts` required by `for_each`.
+    /// The resulting values are valid on grids of any size, including grids narrower than the offsets.
+    fn engine_shifts<const N: usize>(&self, offsets: &[(i32, i32); N]) -> ([usize; N], [usize; N]) {
+        (
+            offsets.map(|(dx, _)| self.engine_x_shift(dx)),
+            offsets.map(|(_, dy)| self.engine_yw_shift(dy)),
+        )
+    }
+    /// Converts `offsets` of any length into the `x_shifts` and `yw_shifts` required by `for_each`.
+    fn engine_shifts_vec(
+        &self,
+        offsets: &[(i32, i32)],
+    ) -> (std::vec::Vec<usize>, std::vec::Vec<usize>) {
+        offsets
+            .iter()
+            .map(|&(dx, dy)| (self.engine_x_shift(dx), self.engine_yw_shift(dy)))
+            .unzip()
+    }
+    /// Converts an x offset into the form `x + w` required by `for_each`.
+    fn engine_x_shift(&self, delta_x: i32) -> usize {
+        WrappingCoords2d::modulo(delta_x, self.w32) as usize + self.wu
+    }
+    /// Converts a y offset into the form `yw + sz` required by `for_each`.
+    fn engine_yw_shift(&self, delta_y: i32) -> usize {
+        WrappingCoords2d::modulo(delta_y, self.h32) as usize * self.wu + self.szu
+    }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and the neighbors defined by `x_shifts` and `yw_shifts`.
     /// This function remains private because `neighbor_shifts` has value restrictions. These coordinates must have
     /// the form (x + w, wy + sz) which elliminates the need to convert between usize and i32.
     /// Despite the increased RAM bandwidth usage, this function keeps `usize` operations to a minimum. `usize` is necessary to prevent
     /// overflow in very large worlds and 32 bit environments.
     ///
+    /// The buffer type `B` is a fixed-size array for the built-in neighborhoods, so the loop does no heap work.
+    ///
     /// # Safety
     ///
     /// This function does not check that `x_shifts` and `yw_shifts` have the same length.
-    fn for_each<F>(
-        &self,
-        mut f: F,
-        mut x_shifts: std::vec::Vec<usize>,
-        mut yw_shifts: std::vec::Vec<usize>,
-    ) where
-        F: FnMut(usize, &std::vec::Vec<usize>),
+    fn for_each<F, B>(&self, mut f: F, mut x_shifts: B, mut yw_shifts: B)
+    where
+        F: FnMut(usize, &B),
+        B: Clone + AsRef<[usize]> + AsMut<[usize]>,
     {
-        // Reusing`x_shifts` and `yw_shifts`
         let x_shifts0 = x_shifts.clone();
-        let mut x = x_shifts.clone();
-        let mut yw = yw_shifts.clone();
-        let mut neighbors = vec![0; x_shifts.len()];
+        let mut neighbors = x_shifts.clone();
         let mut this_cell_index = 0;
         loop {
-            for j in 0..neighbors.len() {
-                x[j] = x_shifts[j] % self.wu;
-                yw[j] = yw_shifts[j] % self.szu;
-                neighbors[j] = yw[j] + x[j];
+            for ((neighbor, &x), &yw) in neighbors
+                .as_mut()
+                .iter_mut()
+                .zip(x_shifts.as_ref())
+                .zip(yw_shifts.as_ref())
+            {
+                *neighbor = yw % self.szu + x % self.wu;
             }
             // Evaluation
             f(this_cell_index, &neighbors);
@@ -390,21 +1385,96 @@ impl WrappingCoords2d {
             }
             // Locate the neighbors
             if this_cell_index % self.wu == 0 {
-                x_shifts = x_shifts0.clone();
-                for j in yw_shifts.iter_mut() {
+                x_shifts.as_mut().copy_from_slice(x_shifts0.as_ref());
+                for j in yw_shifts.as_mut().iter_mut() {
                     *j += self.wu;
                 }
             } else {
-                for j in x_shifts.iter_mut() {
+                for j in x_shifts.as_mut().iter_mut() {
                     *j += 1;
                 }
             }
         }
     }
+    /// Returns a vector of the indices to the neighbors of the cell given by `start_index`, in the order of `offsets`,
+    /// omitting the neighbors beyond an edge with `Boundary::Absorb`.
+    fn neighbors_offsets(
+        &self,
+        start_index: usize,
+        offsets: &[(i32, i32)],
+    ) -> std::vec::Vec<usize> {
+        offsets
+            .iter()
+            .filter_map(|&(dx, dy)| self.shift_checked(start_index, dx, dy))
+            .collect()
+    }
+    /// Calls `f` on each cell of the grid and an array of the neighbors given by `offsets`.
+    /// Grids that do not wrap use `shift` on each cell instead of the engine.
+    fn for_each_array<F, const N: usize>(&self, offsets: &[(i32, i32); N], mut f: F)
+    where
+        F: FnMut(usize, &[usize; N]),
+    {
+        if self.is_torus() {
+            let (x_shifts, yw_shifts) = self.engine_shifts(offsets);
+            self.for_each(f, x_shifts, yw_shifts)
+        } else {
+            assert!(!self.absorbs(), "{}", ABSORBED);
+            for this_cell_index in 0..self.szu {
+                f(
+                    this_cell_index,
+                    &offsets.map(|(dx, dy)| self.shift(this_cell_index, dx, dy)),
+                );
+            }
+        }
+    }
+    /// Calls `f` on each cell of the grid and an array of the neighbors given by `offsets`,
+    /// with `None` for the neighbors beyond an edge with `Boundary::Absorb`.
+    fn for_each_array_checked<F, const N: usize>(&self, offsets: &[(i32, i32); N], mut f: F)
+    where
+        F: FnMut(usize, &[Option<usize>; N]),
+    {
+        if !self.absorbs() {
+            return self.for_each_array(offsets, |index, neighbors| f(index, &neighbors.map(Some)));
+        }
+        for this_cell_index in 0..self.szu {
+            f(
+                this_cell_index,
+                &offsets.map(|(dx, dy)| self.shift_checked(this_cell_index, dx, dy)),
+            );
+        }
+    }
+    /// Calls `f` on each cell of the grid and a slice of the neighbors given by `offsets`,
+    /// omitting the neighbors beyond an edge with `Boundary::Absorb`.
+    /// Grids that do not wrap reuse a single buffer instead of the engine.
+    fn for_each_offsets<F>(&self, offsets: &[(i32, i32)], mut f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        if self.is_torus() {
+            let (x_shifts, yw_shifts) = self.engine_shifts_vec(offsets);
+            self.for_each(|index, neighbors| f(index, neighbors), x_shifts, yw_shifts)
+        } else {
+            let mut neighbors = std::vec::Vec::with_capacity(offsets.len());
+            for this_cell_index in 0..self.szu {
+                neighbors.clear();
+                neighbors.extend(
+                    offsets
+                        .iter()
+                        .filter_map(|&(dx, dy)| self.shift_checked(this_cell_index, dx, dy)),
+                );
+                f(this_cell_index, &neighbors);
+            }
+        }
+    }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 4 neighbors,
     /// the so-called von Neumann neighborhood or 4-neighborhood. The indices are ordered in 2D, counter-clockwise,
     /// starting from the neighbor to the right.
     ///
+    /// # Panics
+    ///
+    /// This function panics on grids with `Boundary::Absorb`.
+    /// Use [`for_each4_checked`](#method.for_each4_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -422,17 +1492,29 @@ impl WrappingCoords2d {
     /// ```
     pub fn for_each4<F>(&self, f: F)
     where
-        F: FnMut(usize, &std::vec::Vec<usize>),
+        F: FnMut(usize, &[usize; 4]),
     {
-        let wp1 = self.wu + 1;
-        let wm1 = self.wu - 1;
-        let spw = self.szu + self.wu;
-        let smw = self.szu - self.wu;
-        self.for_each(
-            f,
-            vec![wp1, self.wu, wm1, self.wu],
-            vec![self.szu, spw, self.szu, smw],
-        )
+        self.for_each_array(&self.offsets.n4, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 4 neighbors, ordered as in
+    /// [`for_each4`](#method.for_each4), with `None` for each neighbor beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// // On a cylinder, the northern neighbor of the top row is missing:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb).unwrap();
+    /// w2d.for_each4_checked(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], w2d.shift_checked(this_cell_index, 1, 0));
+    ///     assert_eq!(neighbors[1].is_none(), this_cell_index >= 90);
+    /// });
+    /// ```
+    pub fn for_each4_checked<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[Option<usize>; 4]),
+    {
+        self.for_each_array_checked(&self.offsets.n4, f)
     }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 4 neighbors,
     /// the so-called von Neumann neighborhood or 4-neighborhood. The indices are ordered in 2D, counter-clockwise,
@@ -454,7 +1536,7 @@ impl WrappingCoords2d {
     where
         F: FnMut(usize, usize),
     {
-        self.for_each4(|this_cell_index, neighbors| {
+        self.for_each_offsets(&self.offsets.n4, |this_cell_index, neighbors| {
             for &neighbor_index in neighbors {
                 f(this_cell_index, neighbor_index);
             }
@@ -479,21 +1561,57 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.neighbors8(0), vec![1, 11, 10, 19, 9, 99, 90, 91]);
     /// ```
     pub fn neighbors8(&self, start_index: usize) -> std::vec::Vec<usize> {
-        // Note: -11 % 10 = -1
-        let idx = start_index as i32;
+        if self.absorbs() {
+            return self.neighbors_offsets(start_index, &self.offsets.n8);
+        }
+        self.neighbors8_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 8 neighbors,
+    /// in the same order as [`neighbors8`](#method.neighbors8). Unlike `neighbors8`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a neighbor lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`neighbors8`](#method.neighbors8) on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors8_array(95), [96, 6, 5, 4, 94, 84, 85, 86]);
+    /// assert_eq!(w2d.neighbors8_array(0), [1, 11, 10, 19, 9, 99, 90, 91]);
+    /// ```
+    pub fn neighbors8_array(&self, start_index: usize) -> [usize; 8] {
+        if !self.is_torus() || !self.order.is_default() {
+            return self
+                .offsets
+                .n8
+                .map(|(dx, dy)| self.shift(start_index, dx, dy));
+        }
+        let idx = (start_index % self.szu) as i32; // Reduced, so that invalid indices stay in the grid
         let x = idx % self.w32; // Always positive
+        let y = idx / self.w32; // Always positive
         let yw = idx - x; // yw: The y coordinate times the width; always positive
-        let idxr1 = (x + 1) % self.w32 + yw; // Index of the first neighbor, the one to the right; modulo is always positive
-        let idxl1 = WrappingCoords2d::modulo(x - 1, self.w32) + yw; // Index of the fourth neighbor, the one to the left; modulo is always positive
-        let mut result32 = vec![idxr1; 8];
-        result32[1] = (idxr1 + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[2] = (idx + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[3] = (idxl1 + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[4] = idxl1;
-        result32[5] = WrappingCoords2d::modulo(idxl1 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[6] = WrappingCoords2d::modulo(idx - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[7] = WrappingCoords2d::modulo(idxr1 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32.into_iter().map(|index| index as usize).collect()
+        let xr1 = WrappingCoords2d::wrapping_add(x, 1, self.w32); // x coordinate of the column to the right
+        let xl1 = WrappingCoords2d::wrapping_add(x, -1, self.w32); // x coordinate of the column to the left
+        let ywa1 = WrappingCoords2d::wrapping_add(y, 1, self.h32) * self.w32; // Row above
+        let ywb1 = WrappingCoords2d::wrapping_add(y, -1, self.h32) * self.w32; // Row below
+        let result32 = [
+            xr1 + yw,
+            xr1 + ywa1,
+            x + ywa1,
+            xl1 + ywa1,
+            xl1 + yw,
+            xl1 + ywb1,
+            x + ywb1,
+            xr1 + ywb1,
+        ];
+        result32.map(|index| index as usize)
     }
     /// This function takes the cell given by `(start_x, start_y)` and returns a vector of the indices to its 8 neighbors,
     /// the so-called Moore neighborhood or 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
@@ -516,6 +1634,11 @@ impl WrappingCoords2d {
     /// the so-called Moore neighborhood or 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
     /// starting from the neighbor to the right.
     ///
+    /// # Panics
+    ///
+    /// This function panics on grids with `Boundary::Absorb`.
+    /// Use [`for_each8_checked`](#method.for_each8_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -537,17 +1660,29 @@ impl WrappingCoords2d {
     /// ```
     pub fn for_each8<F>(&self, f: F)
     where
-        F: FnMut(usize, &std::vec::Vec<usize>),
+        F: FnMut(usize, &[usize; 8]),
     {
-        let wp1 = self.wu + 1;
-        let wm1 = self.wu - 1;
-        let spw = self.szu + self.wu;
-        let smw = self.szu - self.wu;
-        self.for_each(
-            f,
-            vec![wp1, wp1, self.wu, wm1, wm1, wm1, self.wu, wp1],
-            vec![self.szu, spw, spw, spw, self.szu, smw, smw, smw],
-        )
+        self.for_each_array(&self.offsets.n8, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 8 neighbors, ordered as in
+    /// [`for_each8`](#method.for_each8), with `None` for each neighbor beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// // On a cylinder, the 3 northern neighbors of the top row are missing:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb).unwrap();
+    /// w2d.for_each8_checked(|this_cell_index, neighbors| {
+    ///     let missing = neighbors.iter().filter(|n| n.is_none()).count();
+    ///     assert_eq!(missing, if this_cell_index >= 90 || this_cell_index < 10 { 3 } else { 0 });
+    /// });
+    /// ```
+    pub fn for_each8_checked<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[Option<usize>; 8]),
+    {
+        self.for_each_array_checked(&self.offsets.n8, f)
     }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 8 neighbors,
     /// the so-called Moore neighborhood or 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
@@ -569,7 +1704,7 @@ impl WrappingCoords2d {
     where
         F: FnMut(usize, usize),
     {
-        self.for_each8(|this_cell_index, neighbors| {
+        self.for_each_offsets(&self.offsets.n8, |this_cell_index, neighbors| {
             for &neighbor_index in neighbors {
                 f(this_cell_index, neighbor_index);
             }
@@ -594,31 +1729,69 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.neighbors16(0), vec![2, 12, 22, 21, 20, 29, 28, 18, 8, 98, 88, 89, 80, 81, 82, 92]);
     /// ```
     pub fn neighbors16(&self, start_index: usize) -> std::vec::Vec<usize> {
-        // Note: -11 % 10 = -1
-        let idx = start_index as i32;
+        if self.absorbs() {
+            return self.neighbors_offsets(start_index, &self.offsets.n16);
+        }
+        self.neighbors16_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 16 second neighbors,
+    /// in the same order as [`neighbors16`](#method.neighbors16). Unlike `neighbors16`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a neighbor lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`neighbors16`](#method.neighbors16) on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors16_array(95), [97, 7, 17, 16, 15, 14, 13, 3, 93, 83, 73, 74, 75, 76, 77, 87]);
+    /// assert_eq!(w2d.neighbors16_array(0), [2, 12, 22, 21, 20, 29, 28, 18, 8, 98, 88, 89, 80, 81, 82, 92]);
+    /// ```
+    pub fn neighbors16_array(&self, start_index: usize) -> [usize; 16] {
+        if !self.is_torus() || !self.order.is_default() {
+            return self
+                .offsets
+                .n16
+                .map(|(dx, dy)| self.shift(start_index, dx, dy));
+        }
+        let idx = (start_index % self.szu) as i32; // Reduced, so that invalid indices stay in the grid
         let x = idx % self.w32; // Always positive
+        let y = idx / self.w32; // Always positive
         let yw = idx - x; // yw: The y coordinate times the width; always positive
-        let idxr2 = (x + 2) % self.w32 + yw; // Index of the first neighbor, the one to the right; modulo is always positive
-        let idxr1 = (x + 1) % self.w32 + yw; // Index of the first neighbor, the one to the right; modulo is always positive
-        let idxl1 = WrappingCoords2d::modulo(x - 1, self.w32) + yw; // Index of the fourth neighbor, the one to the left; modulo is always positive
-        let idxl2 = WrappingCoords2d::modulo(x - 2, self.w32) + yw; // Index of the fourth neighbor, the one to the left; modulo is always positive
-        let mut result32 = vec![idxr2; 16];
-        result32[1] = (idxr2 + self.w32) % self.sz32;
-        result32[2] = (idxr2 + 2 * self.w32) % self.sz32;
-        result32[3] = (idxr1 + 2 * self.w32) % self.sz32;
-        result32[4] = (idx + 2 * self.w32) % self.sz32;
-        result32[5] = (idxl1 + 2 * self.w32) % self.sz32;
-        result32[6] = (idxl2 + 2 * self.w32) % self.sz32;
-        result32[7] = (idxl2 + self.w32) % self.sz32;
-        result32[8] = idxl2;
-        result32[9] = WrappingCoords2d::modulo(idxl2 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[10] = WrappingCoords2d::modulo(idxl2 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[11] = WrappingCoords2d::modulo(idxl1 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[12] = WrappingCoords2d::modulo(idx - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[13] = WrappingCoords2d::modulo(idxr1 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[14] = WrappingCoords2d::modulo(idxr2 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[15] = WrappingCoords2d::modulo(idxr2 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32.into_iter().map(|index| index as usize).collect()
+        let xr2 = WrappingCoords2d::wrapping_add(x, 2, self.w32); // x coordinate of the second column to the right
+        let xr1 = WrappingCoords2d::wrapping_add(x, 1, self.w32); // x coordinate of the column to the right
+        let xl1 = WrappingCoords2d::wrapping_add(x, -1, self.w32); // x coordinate of the column to the left
+        let xl2 = WrappingCoords2d::wrapping_add(x, -2, self.w32); // x coordinate of the second column to the left
+        let ywa2 = WrappingCoords2d::wrapping_add(y, 2, self.h32) * self.w32; // Second row above
+        let ywa1 = WrappingCoords2d::wrapping_add(y, 1, self.h32) * self.w32; // Row above
+        let ywb1 = WrappingCoords2d::wrapping_add(y, -1, self.h32) * self.w32; // Row below
+        let ywb2 = WrappingCoords2d::wrapping_add(y, -2, self.h32) * self.w32; // Second row below
+        let result32 = [
+            xr2 + yw,
+            xr2 + ywa1,
+            xr2 + ywa2,
+            xr1 + ywa2,
+            x + ywa2,
+            xl1 + ywa2,
+            xl2 + ywa2,
+            xl2 + ywa1,
+            xl2 + yw,
+            xl2 + ywb1,
+            xl2 + ywb2,
+            xl1 + ywb2,
+            x + ywb2,
+            xr1 + ywb2,
+            xr2 + ywb2,
+            xr2 + ywb1,
+        ];
+        result32.map(|index| index as usize)
     }
     /// This function takes the cell given by `(start_x, start_y)` and returns a vector of the indices to its 16 second neighbors,
     /// which are adjacent to the cell's 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
@@ -641,6 +1814,11 @@ impl WrappingCoords2d {
     /// which are adjacent to the cell's 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
     /// starting from the second cell to the right.
     ///
+    /// # Panics
+    ///
+    /// This function panics on grids with `Boundary::Absorb`.
+    /// Use [`for_each16_checked`](#method.for_each16_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -670,28 +1848,31 @@ impl WrappingCoords2d {
     /// ```
     pub fn for_each16<F>(&self, f: F)
     where
-        F: FnMut(usize, &std::vec::Vec<usize>),
+        F: FnMut(usize, &[usize; 16]),
     {
-        let wp2 = self.wu + 2;
-        let wp1 = self.wu + 1;
-        let wm1 = self.wu - 1;
-        let wm2 = self.wu - 2;
-        let w2 = 2 * self.wu;
-        let sp2 = self.szu + w2;
-        let spw = self.szu + self.wu;
-        let smw = self.szu - self.wu;
-        let sm2 = self.szu - w2;
-        self.for_each(
-            f,
-            vec![
-                wp2, wp2, wp2, wp1, self.wu, wm1, wm2, wm2, wm2, wm2, wm2, wm1, self.wu, wp1, wp2,
-                wp2,
-            ],
-            vec![
-                self.szu, spw, sp2, sp2, sp2, sp2, sp2, spw, self.szu, smw, sm2, sm2, sm2, sm2,
-                sm2, smw,
-            ],
-        )
+        self.for_each_array(&self.offsets.n16, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 16 second neighbors, ordered as in
+    /// [`for_each16`](#method.for_each16), with `None` for each neighbor beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// // On a cylinder, the top row loses the 7 second neighbors in the 2 rows above:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb).unwrap();
+    /// w2d.for_each16_checked(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], w2d.shift_checked(this_cell_index, 2, 0));
+    ///     if this_cell_index >= 90 {
+    ///         assert_eq!(neighbors.iter().filter(|n| n.is_none()).count(), 7);
+    ///     }
+    /// });
+    /// ```
+    pub fn for_each16_checked<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[Option<usize>; 16]),
+    {
+        self.for_each_array_checked(&self.offsets.n16, f)
     }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 16 second neighbors,
     /// which are adjacent to the cell's 8-neighborhood. The indices are ordered in 2D, counter-clockwise,
@@ -713,7 +1894,7 @@ impl WrappingCoords2d {
     where
         F: FnMut(usize, usize),
     {
-        self.for_each16(|this_cell_index, neighbors| {
+        self.for_each_offsets(&self.offsets.n16, |this_cell_index, neighbors| {
             for &neighbor_index in neighbors {
                 f(this_cell_index, neighbor_index);
             }
@@ -738,39 +1919,36 @@ impl WrappingCoords2d {
     /// assert_eq!(w2d.neighbors24(0), vec![1, 11, 10, 19, 9, 99, 90, 91, 2, 12, 22, 21, 20, 29, 28, 18, 8, 98, 88, 89, 80, 81, 82, 92]);
     /// ```
     pub fn neighbors24(&self, start_index: usize) -> std::vec::Vec<usize> {
-        // Note: -11 % 10 = -1
-        let idx = start_index as i32;
-        let x = idx % self.w32; // Always positive
-        let yw = idx - x; // yw: The y coordinate times the width; always positive
-        let idxr2 = (x + 2) % self.w32 + yw; // Index of the first neighbor, the one to the right; modulo is always positive
-        let idxr1 = (x + 1) % self.w32 + yw; // Index of the first neighbor, the one to the right; modulo is always positive
-        let idxl1 = WrappingCoords2d::modulo(x - 1, self.w32) + yw; // Index of the fourth neighbor, the one to the left; modulo is always positive
-        let idxl2 = WrappingCoords2d::modulo(x - 2, self.w32) + yw; // Index of the fourth neighbor, the one to the left; modulo is always positive
-        let mut result32 = vec![idxr1; 24];
-        result32[1] = (idxr1 + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[2] = (idx + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[3] = (idxl1 + self.w32) % self.sz32; // Neighbor above; modulo is always positive
-        result32[4] = idxl1;
-        result32[5] = WrappingCoords2d::modulo(idxl1 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[6] = WrappingCoords2d::modulo(idx - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[7] = WrappingCoords2d::modulo(idxr1 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[8] = idxr2;
-        result32[9] = (idxr2 + self.w32) % self.sz32;
-        result32[10] = (idxr2 + 2 * self.w32) % self.sz32;
-        result32[11] = (idxr1 + 2 * self.w32) % self.sz32;
-        result32[12] = (idx + 2 * self.w32) % self.sz32;
-        result32[13] = (idxl1 + 2 * self.w32) % self.sz32;
-        result32[14] = (idxl2 + 2 * self.w32) % self.sz32;
-        result32[15] = (idxl2 + self.w32) % self.sz32;
-        result32[16] = idxl2;
-        result32[17] = WrappingCoords2d::modulo(idxl2 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[18] = WrappingCoords2d::modulo(idxl2 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[19] = WrappingCoords2d::modulo(idxl1 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[20] = WrappingCoords2d::modulo(idx - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[21] = WrappingCoords2d::modulo(idxr1 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[22] = WrappingCoords2d::modulo(idxr2 - 2 * self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32[23] = WrappingCoords2d::modulo(idxr2 - self.w32, self.sz32); // Neighbor below; modulo is always positive
-        result32.into_iter().map(|index| index as usize).collect()
+        if self.absorbs() {
+            return self.neighbors_offsets(start_index, &self.offsets.n24);
+        }
+        self.neighbors24_array(start_index).to_vec()
+    }
+    /// This function takes the cell given by `start_index` and returns an array of the indices to its 24 nearest neighbors,
+    /// in the same order as [`neighbors24`](#method.neighbors24). Unlike `neighbors24`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a neighbor lies beyond an edge with `Boundary::Absorb`.
+    /// Use [`neighbors24`](#method.neighbors24) on such grids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors24_array(95), [96, 6, 5, 4, 94, 84, 85, 86, 97, 7, 17, 16, 15, 14, 13, 3, 93, 83, 73, 74, 75, 76, 77, 87]);
+    /// assert_eq!(w2d.neighbors24_array(0), [1, 11, 10, 19, 9, 99, 90, 91, 2, 12, 22, 21, 20, 29, 28, 18, 8, 98, 88, 89, 80, 81, 82, 92]);
+    /// ```
+    pub fn neighbors24_array(&self, start_index: usize) -> [usize; 24] {
+        let mut result = [0; 24];
+        result[..8].copy_from_slice(&self.neighbors8_array(start_index));
+        result[8..].copy_from_slice(&self.neighbors16_array(start_index));
+        result
     }
     /// This function takes the cell given by `(start_x, start_y)` and returns a vector of the indices to its 24 nearest neighbors.
     /// The indices are ordered in 2D, counter-clockwise, starting with the cell to the right, going through the
@@ -793,6 +1971,11 @@ impl WrappingCoords2d {
     /// The indices are ordered in 2D, counter-clockwise, starting with the cell to the right, going through the
     /// Moore neighborhood first, and then going through the second cell to the right, and ending with the second neighbors.
     ///
+    /// # Panics
+    ///
+    /// This function panics on grids with `Boundary::Absorb`.
+    /// Use [`for_each24_checked`](#method.for_each24_checked) on such grids.
+    ///
     /// # Examples
     ///
     /// ```
@@ -830,28 +2013,30 @@ impl WrappingCoords2d {
     /// ```
     pub fn for_each24<F>(&self, f: F)
     where
-        F: FnMut(usize, &std::vec::Vec<usize>),
+        F: FnMut(usize, &[usize; 24]),
     {
-        let wp2 = self.wu + 2;
-        let wp1 = self.wu + 1;
-        let wm1 = self.wu - 1;
-        let wm2 = self.wu - 2;
-        let w2 = 2 * self.wu;
-        let sp2 = self.szu + w2;
-        let spw = self.szu + self.wu;
-        let smw = self.szu - self.wu;
-        let sm2 = self.szu - w2;
-        self.for_each(
-            f,
-            vec![
-                wp1, wp1, self.wu, wm1, wm1, wm1, self.wu, wp1, wp2, wp2, wp2, wp1, self.wu, wm1,
-                wm2, wm2, wm2, wm2, wm2, wm1, self.wu, wp1, wp2, wp2,
-            ],
-            vec![
-                self.szu, spw, spw, spw, self.szu, smw, smw, smw, self.szu, spw, sp2, sp2, sp2,
-                sp2, sp2, spw, self.szu, smw, sm2, sm2, sm2, sm2, sm2, smw,
-            ],
-        )
+        self.for_each_array(&self.offsets.n24, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its 24 neighbors, ordered as in
+    /// [`for_each24`](#method.for_each24), with `None` for each neighbor beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Boundary, WrappingCoords2d};
+    /// // On a cylinder, the cells of the top row lose the 10 neighbors in the 2 rows above:
+    /// let w2d = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb).unwrap();
+    /// w2d.for_each24_checked(|this_cell_index, neighbors| {
+    ///     if this_cell_index >= 90 {
+    ///         assert_eq!(neighbors.iter().flatten().count(), 14);
+    ///     }
+    /// });
+    /// ```
+    pub fn for_each24_checked<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[Option<usize>; 24]),
+    {
+        self.for_each_array_checked(&self.offsets.n24, f)
     }
     /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 24 nearest neighbors.
     /// The indices are ordered in 2D, counter-clockwise, starting with the cell to the right, going through the
@@ -873,114 +2058,1264 @@ impl WrappingCoords2d {
     where
         F: FnMut(usize, usize),
     {
-        self.for_each24(|this_cell_index, neighbors| {
+        self.for_each_offsets(&self.offsets.n24, |this_cell_index, neighbors| {
             for &neighbor_index in neighbors {
                 f(this_cell_index, neighbor_index);
             }
         });
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        // Basic examples - Copy over to the documentation section
-        let w2d = WrappingCoords2d::new(10, 10).unwrap();
-        // Here are some basic coordinates:
-        assert_eq!(w2d.coords(0), (0, 0));
-        assert_eq!(w2d.coords(1), (1, 0));
-        assert_eq!(w2d.coords(10), (0, 1));
-        assert_eq!(w2d.coords(11), (1, 1));
-        assert_eq!(w2d.coords(90), (0, 9));
-        assert_eq!(w2d.coords(91), (1, 9));
-        // Here are the cell at (5, 9) and its 8 neighbors, counterclockwise, starting from the right neighbor:
-        assert_eq!(w2d.index(5, 9), 95);
-        assert_eq!(w2d.index(6, 9), 96);
-        assert_eq!(w2d.index(6, 0), 6);
-        assert_eq!(w2d.index(5, 0), 5);
-        assert_eq!(w2d.index(4, 0), 4);
-        assert_eq!(w2d.index(4, 9), 94);
-        assert_eq!(w2d.index(4, 8), 84);
-        assert_eq!(w2d.index(5, 8), 85);
-        assert_eq!(w2d.index(6, 8), 86);
-        // Here are the cell at (0, 0) and its 8 neighbors, counterclockwise, starting from the right neighbor:
-        assert_eq!(w2d.index(0, 0), 0);
-        assert_eq!(w2d.index(1, 0), 1);
-        assert_eq!(w2d.index(1, 1), 11);
-        assert_eq!(w2d.index(0, 1), 10);
-        assert_eq!(w2d.index(-1, 1), 19);
-        assert_eq!(w2d.index(-1, 0), 9);
-        assert_eq!(w2d.index(-1, -1), 99);
-        assert_eq!(w2d.index(0, -1), 90);
-        assert_eq!(w2d.index(1, -1), 91);
-        // Here are the 8 neighbors of the cell at (5, 9), counterclockwise, starting from the right neighbor:
-        assert_eq!(w2d.shift(95, 1, 0), 96);
-        assert_eq!(w2d.shift(95, 1, 1), 6);
-        assert_eq!(w2d.shift(95, 0, 1), 5);
-        assert_eq!(w2d.shift(95, -1, 1), 4);
-        assert_eq!(w2d.shift(95, -1, 0), 94);
-        assert_eq!(w2d.shift(95, -1, -1), 84);
-        assert_eq!(w2d.shift(95, 0, -1), 85);
-        assert_eq!(w2d.shift(95, 1, -1), 86);
-        // Here are the 8 neighbors of the cell at (0, 0), counterclockwise, starting from the right neighbor:
-        assert_eq!(w2d.shift(0, 1, 0), 1);
-        assert_eq!(w2d.shift(0, 1, 1), 11);
-        assert_eq!(w2d.shift(0, 0, 1), 10);
-        assert_eq!(w2d.shift(0, -1, 1), 19);
-        assert_eq!(w2d.shift(0, -1, 0), 9);
-        assert_eq!(w2d.shift(0, -1, -1), 99);
-        assert_eq!(w2d.shift(0, 0, -1), 90);
-        assert_eq!(w2d.shift(0, 1, -1), 91);
-
-        // End of basic examples
-        // Easy-to-read tests; may repeat some basic examples
-        assert_eq!(w2d.shift(0, 1, 0), 1);
-        assert_eq!(w2d.shift(0, 11, 0), 1);
-        assert_eq!(w2d.shift(0, -1, 0), 9);
-        assert_eq!(w2d.shift(0, -11, 0), 9);
-        assert_eq!(w2d.shift(10, 1, 0), 11);
-        assert_eq!(w2d.shift(10, 11, 0), 11);
-        assert_eq!(w2d.shift(10, -1, 0), 19);
-        assert_eq!(w2d.shift(10, -11, 0), 19);
-        assert_eq!(w2d.shift(0, 0, 1), 10);
-        assert_eq!(w2d.shift(0, 0, 11), 10);
-        assert_eq!(w2d.shift(0, 0, -1), 90);
-        assert_eq!(w2d.shift(0, 0, -11), 90);
-        assert_eq!(w2d.shift(10, 0, 1), 20);
-        assert_eq!(w2d.shift(10, 0, 11), 20);
-        assert_eq!(w2d.shift(10, 0, -1), 0);
-        assert_eq!(w2d.shift(10, 0, -11), 0);
-
-        // More tests
-        let grids = vec![
-            WrappingCoords2d::new(10, 10).unwrap(),
-            WrappingCoords2d::new(20, 20).unwrap(),
-            WrappingCoords2d::new(100, 100).unwrap(),
-            WrappingCoords2d::new(21, 2).unwrap(),
-            WrappingCoords2d::new(1, 1).unwrap(),
-            WrappingCoords2d::new(10000, 10).unwrap(),
-            WrappingCoords2d::new(10000, 10000).unwrap(),
-            WrappingCoords2d::new(1, 10000000).unwrap(),
-        ];
-        for g in grids {
-            assert_eq!(g.shift(0, 1, 0), 1 % g.wu);
-            assert_eq!(g.shift(0, 1, 20), (20 * g.wu + (1 % g.wu)) % g.szu);
-            assert_eq!(g.shift(0, 20, 20), (20 * g.wu + (20 % g.wu)) % g.szu);
-            assert_eq!(
-                g.shift(0, 200000, 200000),
-                (200000 * g.wu + (200000 % g.wu)) % g.szu
-            );
-            let x1 = 10000;
-            let y1 = 10000;
-            let idx1 = g.index(x1, y1);
-            assert_eq!(g.shift(idx1, 1, 0), g.index(x1 + 1, y1));
-            assert_eq!(g.shift(idx1, 1, 20), g.index(x1 + 1, y1 + 20));
-            assert_eq!(g.shift(idx1, 20, 20), g.index(x1 + 20, y1 + 20));
-            assert_eq!(
-                g.shift(idx1, 200000, 200000),
+    /// Returns a lazy iterator over the indices to the neighbors of the cell given by `start_index`.
+    /// The iterator yields the same indices, in the same counter-clockwise order, as the matching function
+    /// `neighbors4`, `neighbors8`, `neighbors16`, or `neighbors24`, without allocating memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it yields valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Neighborhood, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let neighbors: Vec<usize> = w2d.neighbors_iter(95, Neighborhood::N4).collect();
+    /// assert_eq!(neighbors, vec![96, 5, 94, 85]);
+    /// // The iterator knows its length and composes with other adapters:
+    /// let iter = w2d.neighbors_iter(0, Neighborhood::N8);
+    /// assert_eq!(iter.len(), 8);
+    /// assert_eq!(iter.filter(|&index| index >= 90).count(), 3);
+    /// ```
+    pub fn neighbors_iter(&self, start_index: usize, neighborhood: Neighborhood) -> Neighbors<'_> {
+        let (x, y) = self.grid_coords(start_index);
+        Neighbors {
+            w2d: self,
+            x,
+            y,
+            offsets: neighborhood.offsets(&self.offsets).iter(),
+        }
+    }
+    /// Returns a lazy iterator over the indices to the neighbors of the cell given by `(start_x, start_y)`.
+    /// The iterator yields the same indices, in the same counter-clockwise order, as the matching function
+    /// `neighbors4xy`, `neighbors8xy`, `neighbors16xy`, or `neighbors24xy`, without allocating memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Neighborhood, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let neighbors: Vec<usize> = w2d.neighbors_iterxy(0, 0, Neighborhood::N8).collect();
+    /// assert_eq!(neighbors, vec![1, 11, 10, 19, 9, 99, 90, 91]);
+    /// ```
+    pub fn neighbors_iterxy(
+        &self,
+        start_x: i32,
+        start_y: i32,
+        neighborhood: Neighborhood,
+    ) -> Neighbors<'_> {
+        self.neighbors_iter(self.index(start_x, start_y), neighborhood)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its neighbors,
+    /// in the same order as [`neighbors_iter`](#method.neighbors_iter), each one with the flips caused by crossing
+    /// twisted edges on the way from the cell. See [`shift_oriented`](#method.shift_oriented).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{Flip, Neighborhood, Topology, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new_with_topology(10, 10, Topology::ProjectivePlane).unwrap();
+    /// let neighbors = w2d.neighbors_oriented(0, Neighborhood::N4);
+    /// assert_eq!(neighbors[0], (1, Flip::default()));
+    /// // Crossing the left edge mirrors the y axis, and crossing the bottom edge mirrors the x axis:
+    /// assert_eq!(neighbors[2], (99, Flip { x: false, y: true }));
+    /// assert_eq!(neighbors[3], (99, Flip { x: true, y: false }));
+    /// ```
+    pub fn neighbors_oriented(
+        &self,
+        start_index: usize,
+        neighborhood: Neighborhood,
+    ) -> std::vec::Vec<(usize, Flip)> {
+        let (x, y) = self.grid_coords(start_index);
+        neighborhood
+            .offsets(&self.offsets)
+            .iter()
+            .filter_map(|&(dx, dy)| self.walk(x, y, dx.into(), dy.into()))
+            .collect()
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its Moore neighborhood
+    /// of radius `radius`: every cell whose x and y offsets lie in the range [-radius, radius], except the cell itself.
+    /// The indices are ordered ring by ring, starting with the closest ring. Each ring is ordered in 2D,
+    /// counter-clockwise, starting from the cell to the right. For radius 1, this is the order of `neighbors8`;
+    /// for radius 2, this is the order of `neighbors24`.
+    ///
+    /// Radii larger than `width + height` are reduced to `width + height`, because farther rings only repeat
+    /// the cells of closer rings, or lie beyond an edge.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors_moore(0, 1), w2d.neighbors8(0));
+    /// assert_eq!(w2d.neighbors_moore(0, 2), w2d.neighbors24(0));
+    /// let neighbors = w2d.neighbors_moore(55, 3);
+    /// assert_eq!(neighbors.len(), 48);
+    /// // The third ring starts with the third cell to the right:
+    /// assert_eq!(neighbors[24], 58);
+    /// ```
+    pub fn neighbors_moore(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(
+            start_index,
+            &self.arrange(moore_offsets(self.reach(radius)), chebyshev_length),
+        )
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its von Neumann
+    /// neighborhood of radius `radius`: every cell whose Manhattan distance to the cell lies in the range [1, radius].
+    /// The indices are ordered ring by ring, starting with the closest ring. Each ring is ordered in 2D,
+    /// counter-clockwise, starting from the cell to the right. For radius 1, this is the order of `neighbors4`.
+    ///
+    /// Radii larger than `width + height` are reduced to `width + height`, because farther rings only repeat
+    /// the cells of closer rings, or lie beyond an edge.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors_von_neumann(0, 1), w2d.neighbors4(0));
+    /// // Here is the second ring of the cell at (5, 5), counterclockwise, starting from the right:
+    /// assert_eq!(w2d.neighbors_von_neumann(55, 2)[4..], [57, 66, 75, 64, 53, 44, 35, 46]);
+    /// ```
+    pub fn neighbors_von_neumann(&self, start_index: usize, radius: u32) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(
+            start_index,
+            &self.arrange(von_neumann_offsets(self.reach(radius)), manhattan_length),
+        )
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its Moore neighborhood of radius `radius`,
+    /// ordered as in [`neighbors_moore`](#method.neighbors_moore).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_moore(3, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 48);
+    ///     assert_eq!(neighbors[24], w2d.shift(this_cell_index, 3, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each_moore<F>(&self, radius: u32, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.for_each_stencil(
+            &self.stencil(&self.arrange(moore_offsets(self.reach(radius)), chebyshev_length)),
+            f,
+        )
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its von Neumann neighborhood
+    /// of radius `radius`, ordered as in [`neighbors_von_neumann`](#method.neighbors_von_neumann).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_von_neumann(3, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 24);
+    ///     assert_eq!(neighbors[12], w2d.shift(this_cell_index, 3, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each_von_neumann<F>(&self, radius: u32, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.for_each_stencil(
+            &self.stencil(&self.arrange(von_neumann_offsets(self.reach(radius)), manhattan_length)),
+            f,
+        )
+    }
+    /// Returns a [`Stencil`] with the neighbor offsets `(dx, dy)`, converted for use with this grid.
+    /// Offsets can take any value in the range of `i32`, and they can repeat or include `(0, 0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// // The 8 moves of a chess knight, counterclockwise, starting from the right:
+    /// let knight = w2d.stencil(&[(2, 1), (1, 2), (-1, 2), (-2, 1), (-2, -1), (-1, -2), (1, -2), (2, -1)]);
+    /// assert_eq!(knight.len(), 8);
+    /// assert_eq!(knight.offsets()[0], (2, 1));
+    /// ```
+    pub fn stencil(&self, offsets: &[(i32, i32)]) -> Stencil {
+        let (x_shifts, yw_shifts) = self.engine_shifts_vec(offsets);
+        Stencil {
+            offsets: offsets.to_vec(),
+            w32: self.w32,
+            h32: self.h32,
+            x_shifts,
+            yw_shifts,
+        }
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its neighbors
+    /// given by `stencil`, in the order of the stencil offsets.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// // A wind blowing to the east carries seeds one or two cells away:
+    /// let wind = w2d.stencil(&[(1, 0), (2, 0), (1, 1), (1, -1)]);
+    /// assert_eq!(w2d.neighbors_stencil(99, &wind), vec![90, 91, 0, 80]);
+    /// ```
+    pub fn neighbors_stencil(&self, start_index: usize, stencil: &Stencil) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &stencil.offsets)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and its neighbors given by `stencil`,
+    /// in the order of the stencil offsets.
+    ///
+    /// A stencil works on any grid; a stencil made for a grid with different dimensions is converted on each call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let knight = w2d.stencil(&[(2, 1), (1, 2), (-1, 2), (-2, 1), (-2, -1), (-1, -2), (1, -2), (2, -1)]);
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_stencil(&knight, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 8);
+    ///     assert_eq!(neighbors[1], w2d.shift(this_cell_index, 1, 2));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each_stencil<F>(&self, stencil: &Stencil, mut f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        if !self.is_torus() {
+            return self.for_each_offsets(&stencil.offsets, f);
+        }
+        let (x_shifts, yw_shifts) = if stencil.w32 == self.w32 && stencil.h32 == self.h32 {
+            (stencil.x_shifts.clone(), stencil.yw_shifts.clone())
+        } else {
+            self.engine_shifts_vec(&stencil.offsets)
+        };
+        self.for_each(|index, neighbors| f(index, neighbors), x_shifts, yw_shifts)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to every cell whose
+    /// Euclidean distance to the cell is at most `radius`, except the cell itself. The indices are ordered by distance;
+    /// indices at the same distance are ordered in 2D, counter-clockwise, starting from the right.
+    ///
+    /// The distance is the length of the shortest [`displacement`](#method.displacement) between the cells,
+    /// so each cell appears once, even on grids smaller than the disk. An infinite `radius` covers the whole grid.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// // With radius 1, the disk is the 4-neighborhood:
+    /// assert_eq!(w2d.neighbors_disk(55, 1.0), w2d.neighbors4(55));
+    /// // With radius 1.5, the diagonal neighbors follow, counterclockwise, starting from the upper right:
+    /// assert_eq!(w2d.neighbors_disk(55, 1.5), vec![56, 65, 54, 45, 66, 64, 44, 46]);
+    /// // The cells 5 columns to the left are the cells 5 columns to the right:
+    /// assert_eq!(w2d.neighbors_disk(55, 5.0).len(), 78);
+    /// assert_eq!(w2d.neighbors_disk(55, f64::INFINITY).len(), 99);
+    /// ```
+    pub fn neighbors_disk(&self, start_index: usize, radius: f64) -> std::vec::Vec<usize> {
+        self.neighbors_ring(start_index, 0.0, radius)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to every cell whose
+    /// Euclidean distance to the cell lies in the range [`inner_radius`, `outer_radius`], except the cell itself.
+    /// The indices are ordered by distance; indices at the same distance are ordered in 2D, counter-clockwise,
+    /// starting from the right. Distances are measured as in [`neighbors_disk`](#method.neighbors_disk).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// // Here are the cells at a distance of exactly 2, counterclockwise, starting from the right:
+    /// assert_eq!(w2d.neighbors_ring(55, 2.0, 2.0), vec![57, 75, 53, 35]);
+    /// assert!(w2d.neighbors_ring(55, 3.0, 2.0).is_empty());
+    /// ```
+    pub fn neighbors_ring(
+        &self,
+        start_index: usize,
+        inner_radius: f64,
+        outer_radius: f64,
+    ) -> std::vec::Vec<usize> {
+        self.ring_candidates(inner_radius, outer_radius)
+            .iter()
+            .filter_map(|&offset| self.shortest_shift(start_index, offset))
+            .collect()
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and every cell whose Euclidean distance
+    /// to the cell is at most `radius`, ordered as in [`neighbors_disk`](#method.neighbors_disk).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// w2d.for_each_disk(2.5, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 20);
+    ///     assert_eq!(neighbors[0], w2d.shift(this_cell_index, 1, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, w2d.size());
+    /// ```
+    pub fn for_each_disk<F>(&self, radius: f64, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.for_each_ring(0.0, radius, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and every cell whose Euclidean distance
+    /// to the cell lies in the range [`inner_radius`, `outer_radius`], ordered as in [`neighbors_ring`](#method.neighbors_ring).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// w2d.for_each_ring(2.0, 2.5, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors.len(), 12);
+    ///     assert_eq!(neighbors[0], w2d.shift(this_cell_index, 2, 0));
+    /// });
+    /// ```
+    pub fn for_each_ring<F>(&self, inner_radius: f64, outer_radius: f64, mut f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        let mut offsets = self.ring_candidates(inner_radius, outer_radius);
+        if self.is_torus() {
+            // Every cell of the torus has the same shortest displacements
+            offsets.retain(|&offset| self.shortest_shift(0, offset).is_some());
+            return self.for_each_stencil(&self.stencil(&offsets), f);
+        }
+        let mut neighbors = std::vec::Vec::with_capacity(offsets.len());
+        for this_cell_index in 0..self.szu {
+            neighbors.clear();
+            neighbors.extend(
+                offsets
+                    .iter()
+                    .filter_map(|&offset| self.shortest_shift(this_cell_index, offset)),
+            );
+            f(this_cell_index, &neighbors);
+        }
+    }
+    /// Returns the offsets of a ring, arranged in the order of the grid.
+    /// The offsets are limited to the largest components of a shortest displacement, so that huge radii stay cheap.
+    fn ring_candidates(&self, inner_radius: f64, outer_radius: f64) -> std::vec::Vec<(i32, i32)> {
+        let (w, h) = (self.w32 as i64, self.h32 as i64);
+        let half = |len: i64| len / 2;
+        let edge = |boundary: Boundary, len: i64| match boundary {
+            Boundary::Wrap => len / 2,
+            _ => len - 1,
+        };
+        // On a lattice, the shortest displacement is no longer than half the diagonal;
+        // twisted edges are crossed at most once along the shortest displacement
+        let half_diagonal = (w + h + 1) / 2;
+        let (rx, ry) = match self.topology {
+            Topology::Torus => (edge(self.x_boundary, w), edge(self.y_boundary, h)),
+            Topology::ShiftedTorus(_) => (half(w), half_diagonal),
+            Topology::Helical => (half_diagonal, half(h)),
+            _ => (2 * w - 1, 2 * h - 1),
+        };
+        let extent = (
+            rx.min(i32::MAX as i64) as i32,
+            ry.min(i32::MAX as i64) as i32,
+        );
+        self.arrange(
+            ring_offsets(inner_radius, outer_radius, extent),
+            squared_length,
+        )
+    }
+    /// Returns the cell reached by moving `offset` from the cell given by `start_index`,
+    /// or `None` if `offset` is not the shortest displacement to that cell.
+    fn shortest_shift(&self, start_index: usize, offset: (i32, i32)) -> Option<usize> {
+        self.shift_checked(start_index, offset.0, offset.1)
+            .filter(|&index| self.displacement(start_index, index) == offset)
+    }
+    /// Calls a closure `f` once for each undirected edge of the grid that joins two distinct cells in the same
+    /// 4-neighborhood. Each call receives the two indices of an edge, the smaller index first.
+    /// In contrast, `for_each_pair4` visits each edge twice, once from each end.
+    ///
+    /// On grids narrower than 3 cells, wrapping makes some cells their own neighbors, or joins two cells through
+    /// both sides; this function skips the former and visits the latter once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mut edges_counter = 0;
+    /// w2d.for_each_edge4(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index < neighbor_index);
+    ///     edges_counter += 1;
+    /// });
+    /// assert_eq!(edges_counter, 2 * w2d.size());
+    /// // In a 2x2 grid, the left and right neighbors coincide:
+    /// let w2d = WrappingCoords2d::new(2, 2).unwrap();
+    /// let mut edges = Vec::new();
+    /// w2d.for_each_edge4(|a, b| edges.push((a, b)));
+    /// assert_eq!(edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+    /// ```
+    pub fn for_each_edge4<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        if !self.is_torus() {
+            return self.for_each_edge_offsets(&self.offsets.n4, f);
+        }
+        self.for_each4(|this_cell_index, neighbors| {
+            WrappingCoords2d::visit_edges(this_cell_index, neighbors, &mut f)
+        });
+    }
+    /// Calls a closure `f` once for each undirected edge of the grid that joins two distinct cells in the same
+    /// 8-neighborhood. Each call receives the two indices of an edge, the smaller index first.
+    /// In contrast, `for_each_pair8` visits each edge twice, once from each end.
+    ///
+    /// On grids narrower than 3 cells, wrapping makes some cells their own neighbors, or joins two cells through
+    /// several sides; this function skips the former and visits the latter once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mut edges_counter = 0;
+    /// w2d.for_each_edge8(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index < neighbor_index);
+    ///     edges_counter += 1;
+    /// });
+    /// assert_eq!(edges_counter, 4 * w2d.size());
+    /// // In a 2x2 grid, every cell is a neighbor of every other cell:
+    /// let w2d = WrappingCoords2d::new(2, 2).unwrap();
+    /// let mut edges_counter = 0;
+    /// w2d.for_each_edge8(|_, _| edges_counter += 1);
+    /// assert_eq!(edges_counter, 6);
+    /// ```
+    pub fn for_each_edge8<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        if !self.is_torus() {
+            return self.for_each_edge_offsets(&self.offsets.n8, f);
+        }
+        self.for_each8(|this_cell_index, neighbors| {
+            WrappingCoords2d::visit_edges(this_cell_index, neighbors, &mut f)
+        });
+    }
+    /// Calls a closure `f` once for each undirected edge of the grid that joins two distinct cells through an offset
+    /// of `stencil`. Each call receives the two indices of an edge, the smaller index first.
+    /// The stencil does not need to be symmetric: two cells are joined if either one is a neighbor of the other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// // The knight moves, given in one direction only:
+    /// let knight = w2d.stencil(&[(2, 1), (1, 2), (-1, 2), (-2, 1)]);
+    /// let mut edges_counter = 0;
+    /// w2d.for_each_edge_stencil(&knight, |this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index < neighbor_index);
+    ///     edges_counter += 1;
+    /// });
+    /// assert_eq!(edges_counter, 4 * w2d.size());
+    /// ```
+    pub fn for_each_edge_stencil<F>(&self, stencil: &Stencil, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        if !self.is_torus() {
+            return self.for_each_edge_offsets(&stencil.offsets, f);
+        }
+        let mut offsets: std::vec::Vec<(i32, i32)> =
+            std::vec::Vec::with_capacity(2 * stencil.len());
+        for &(dx, dy) in stencil.offsets.iter() {
+            // Negating i32::MIN overflows, so the reverse offset is computed modulo the dimensions of the grid
+            let reverse = (
+                WrappingCoords2d::modulo(dx, self.w32).wrapping_neg(),
+                WrappingCoords2d::modulo(dy, self.h32).wrapping_neg(),
+            );
+            for offset in [(dx, dy), reverse].iter() {
+                if *offset != (0, 0) && !offsets.contains(offset) {
+                    offsets.push(*offset);
+                }
+            }
+        }
+        self.for_each_stencil(&self.stencil(&offsets), |this_cell_index, neighbors| {
+            WrappingCoords2d::visit_edges(this_cell_index, neighbors, &mut f)
+        });
+    }
+    /// Calls `f` once for each undirected edge of a grid that does not wrap, joining two distinct cells through
+    /// an offset of `offsets`. Clamped and reflected moves make adjacency asymmetric near the edges, so each edge
+    /// is visited from its smaller end, unless only the larger end reaches the other one.
+    fn for_each_edge_offsets<F>(&self, offsets: &[(i32, i32)], mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each_offsets(offsets, |this_cell_index, neighbors| {
+            for (j, &neighbor_index) in neighbors.iter().enumerate() {
+                if neighbor_index == this_cell_index || neighbors[..j].contains(&neighbor_index) {
+                    continue;
+                }
+                if neighbor_index > this_cell_index {
+                    f(this_cell_index, neighbor_index);
+                } else if !self
+                    .neighbors_offsets(neighbor_index, offsets)
+                    .contains(&this_cell_index)
+                {
+                    f(neighbor_index, this_cell_index);
+                }
+            }
+        });
+    }
+    /// Calls `f` on each edge between `this_cell_index` and a larger index in `neighbors`, skipping repeated neighbors.
+    /// If adjacency is symmetric, this visits each undirected edge of the grid exactly once.
+    fn visit_edges<F>(this_cell_index: usize, neighbors: &[usize], f: &mut F)
+    where
+        F: FnMut(usize, usize),
+    {
+        for (j, &neighbor_index) in neighbors.iter().enumerate() {
+            if neighbor_index > this_cell_index && !neighbors[..j].contains(&neighbor_index) {
+                f(this_cell_index, neighbor_index);
+            }
+        }
+    }
+    /// Returns the shortest displacement `(dx, dy)` from the cell given by `from_index` to the cell given by `to_index`,
+    /// following the minimal image convention: `shift(from_index, dx, dy) == to_index`, and each component is the
+    /// shortest wrapped distance along its axis.
+    ///
+    /// When a dimension is even, a cell exactly half the grid away is equally close in both directions;
+    /// this function breaks the tie in favor of the positive direction. For example, in a grid of width 10,
+    /// `dx` lies in the range [-4, 5].
+    ///
+    /// Along an axis that does not wrap, the component is the plain difference of the coordinates.
+    /// On a grid with twisted edges, the shortest displacement is the one with the smallest Euclidean length;
+    /// see [Topologies](#topologies).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `from_index` and `to_index` are valid indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.displacement(0, 11), (1, 1));
+    /// // The shortest way from (0, 0) to (9, 9) wraps around both edges:
+    /// assert_eq!(w2d.displacement(0, 99), (-1, -1));
+    /// // Ties favor the positive direction:
+    /// assert_eq!(w2d.displacement(0, 55), (5, 5));
+    /// assert_eq!(w2d.displacement(55, 0), (5, 5));
+    /// ```
+    pub fn displacement(&self, from_index: usize, to_index: usize) -> (i32, i32) {
+        match self.topology {
+            Topology::Torus => {}
+            Topology::Helical | Topology::ShiftedTorus(_) => {
+                return self.lattice_displacement(from_index, to_index)
+            }
+            _ => return self.twisted_displacement(from_index, to_index),
+        }
+        let (x0, y0) = self.grid_coords(from_index);
+        let (x1, y1) = self.grid_coords(to_index);
+        let dx = match self.x_boundary {
+            Boundary::Wrap => WrappingCoords2d::minimal_image(x1 - x0, self.w32),
+            _ => x1 - x0,
+        };
+        let dy = match self.y_boundary {
+            Boundary::Wrap => WrappingCoords2d::minimal_image(y1 - y0, self.h32),
+            _ => y1 - y0,
+        };
+        (dx, dy)
+    }
+    /// Returns the shortest displacement on a helical or shifted torus. Both are the plane modulo a lattice of moves
+    /// that return to the same cell, so this function solves the closest vector problem with a reduced basis.
+    fn lattice_displacement(&self, from_index: usize, to_index: usize) -> (i32, i32) {
+        let (x0, y0) = self.grid_coords(from_index);
+        let (x1, y1) = self.grid_coords(to_index);
+        let (w, h) = (self.w32 as i128, self.h32 as i128);
+        let dot = |a: (i128, i128), b: (i128, i128)| a.0 * b.0 + a.1 * b.1;
+        // Moves that return to the same cell
+        let (mut b1, mut b2) = match self.topology {
+            Topology::ShiftedTorus(shift) => ((w, 0), (-(shift as i128).rem_euclid(w), h)),
+            _ => ((w, -1), (0, h)),
+        };
+        // Lagrange-Gauss reduction
+        loop {
+            if dot(b1, b1) > dot(b2, b2) {
+                std::mem::swap(&mut b1, &mut b2);
+            }
+            let n1 = dot(b1, b1);
+            let mu = (2 * dot(b1, b2) + n1).div_euclid(2 * n1); // Rounded quotient
+            if mu == 0 {
+                break;
+            }
+            b2 = (b2.0 - mu * b1.0, b2.1 - mu * b1.1);
+        }
+        // The direct move reaches the cell without crossing any edge
+        let t = ((x1 - x0) as i128, (y1 - y0) as i128);
+        if b1.0 * b2.1 < b1.1 * b2.0 {
+            std::mem::swap(&mut b1, &mut b2);
+        }
+        // Coordinates of `t` in the reduced basis, rounded down
+        let det = b1.0 * b2.1 - b1.1 * b2.0; // Positive number
+        let c1 = (t.0 * b2.1 - t.1 * b2.0).div_euclid(det);
+        let c2 = (b1.0 * t.1 - b1.1 * t.0).div_euclid(det);
+        let mut best = t;
+        for k1 in c1 - 1..=c1 + 2 {
+            for k2 in c2 - 1..=c2 + 2 {
+                let v = (t.0 - k1 * b1.0 - k2 * b2.0, t.1 - k1 * b1.1 - k2 * b2.1);
+                // Ties favor the positive directions, as in the torus
+                let key = |v: (i128, i128)| (dot(v, v), -v.0, -v.1);
+                if key(v) < key(best) {
+                    best = v;
+                }
+            }
+        }
+        (best.0 as i32, best.1 as i32)
+    }
+    /// Returns the shortest displacement on a grid with twisted edges. Each axis that wraps is crossed at most once
+    /// along the shortest displacement, so this function compares the displacements that cross each edge -1, 0, or 1 times.
+    fn twisted_displacement(&self, from_index: usize, to_index: usize) -> (i32, i32) {
+        let (x0, y0) = self.grid_coords(from_index);
+        let (x1, y1) = self.grid_coords(to_index);
+        let (w, h) = (self.w32 as i64, self.h32 as i64);
+        let y_crossings: &[i64] = if self.y_boundary == Boundary::Wrap {
+            &[-1, 0, 1]
+        } else {
+            &[0]
+        };
+        let mut candidates = std::vec::Vec::with_capacity(9);
+        for &kx in [-1, 0, 1].iter() {
+            for &ky in y_crossings {
+                // Crossing the y edge an odd number of times mirrors the x coordinate at the end of the move
+                let target_x = if self.topology.twists_y() && ky % 2 != 0 {
+                    w - 1 - x1 as i64
+                } else {
+                    x1 as i64
+                };
+                let dx = target_x - x0 as i64 + kx * w;
+                // Crossing the x edge an odd number of times mirrors the y coordinate and the y offset
+                let dy = if self.topology.twists_x() && kx % 2 != 0 {
+                    -(y1 as i64 + ky * h - (h - 1 - y0 as i64))
+                } else {
+                    y1 as i64 + ky * h - y0 as i64
+                };
+                if let (Ok(dx), Ok(dy)) = (i32::try_from(dx), i32::try_from(dy)) {
+                    if self.locate(x0, y0, dx.into(), dy.into()) == Some(to_index) {
+                        candidates.push((dx, dy));
+                    }
+                }
+            }
+        }
+        // Ties favor the positive directions, as in the torus
+        candidates
+            .into_iter()
+            .min_by_key(|&(dx, dy)| {
+                (
+                    squared_length((dx, dy)),
+                    std::cmp::Reverse(dx),
+                    std::cmp::Reverse(dy),
+                )
+            })
+            .expect("the direct displacement always reaches the cell")
+    }
+    /// Returns the shortest representative of `delta` modulo `rhs`, in the range (-rhs / 2, rhs / 2].
+    /// `delta` must lie in the range (-rhs, rhs).
+    fn minimal_image(delta: i32, rhs: i32) -> i32 {
+        let d = WrappingCoords2d::modulo(delta, rhs); // Positive number
+        if d > rhs - d {
+            d - rhs
+        } else {
+            d
+        }
+    }
+    /// Returns the Manhattan distance between two cells on the wrapping grid: the number of steps
+    /// through the 4-neighborhood along the shortest path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.manhattan_distance(0, 11), 2);
+    /// assert_eq!(w2d.manhattan_distance(0, 99), 2);
+    /// assert_eq!(w2d.manhattan_distance(0, 55), 10);
+    /// ```
+    pub fn manhattan_distance(&self, from_index: usize, to_index: usize) -> i32 {
+        let (dx, dy) = self.displacement(from_index, to_index);
+        dx.abs() + dy.abs()
+    }
+    /// Returns the Chebyshev distance between two cells on the wrapping grid: the number of steps
+    /// through the 8-neighborhood along the shortest path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.chebyshev_distance(0, 11), 1);
+    /// assert_eq!(w2d.chebyshev_distance(0, 92), 2);
+    /// assert_eq!(w2d.chebyshev_distance(0, 55), 5);
+    /// ```
+    pub fn chebyshev_distance(&self, from_index: usize, to_index: usize) -> i32 {
+        let (dx, dy) = self.displacement(from_index, to_index);
+        dx.abs().max(dy.abs())
+    }
+    /// Returns the squared Euclidean distance between the centers of two cells on the wrapping grid.
+    /// Unlike [`euclidean_distance`](#method.euclidean_distance), this function is exact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.squared_distance(0, 11), 2);
+    /// assert_eq!(w2d.squared_distance(0, 92), 5);
+    /// assert_eq!(w2d.squared_distance(0, 55), 50);
+    /// ```
+    pub fn squared_distance(&self, from_index: usize, to_index: usize) -> i64 {
+        squared_length(self.displacement(from_index, to_index))
+    }
+    /// Returns the Euclidean distance between the centers of two cells on the wrapping grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.euclidean_distance(0, 1), 1.0);
+    /// assert_eq!(w2d.euclidean_distance(0, 43), 5.0);
+    /// assert_eq!(w2d.euclidean_distance(0, 67), 5.0);
+    /// ```
+    pub fn euclidean_distance(&self, from_index: usize, to_index: usize) -> f64 {
+        (self.squared_distance(from_index, to_index) as f64).sqrt()
+    }
+}
+
+/// Represents the behavior of an axis of a [`WrappingCoords2d`] grid beyond its edges.
+/// See [Boundaries](struct.WrappingCoords2d.html#boundaries).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Coordinates wrap around to the opposite edge. This is the default policy.
+    #[default]
+    Wrap,
+    /// Coordinates stop at the nearest edge.
+    Clamp,
+    /// Coordinates bounce off the edge as if it were a mirror through the centers of the edge cells.
+    /// For example, along an axis of length 10, `-1` maps to `1`, and `10` maps to `8`.
+    Reflect,
+    /// Coordinates beyond the edges do not map to any cell.
+    Absorb,
+}
+
+impl Boundary {
+    /// Maps the coordinate `c` into the range [0, len), or returns `None` if the coordinate is absorbed.
+    fn resolve(self, c: i128, len: i32) -> Option<i32> {
+        let len = len as i128;
+        let mapped = match self {
+            Boundary::Wrap => c.rem_euclid(len),
+            Boundary::Clamp => c.clamp(0, len - 1),
+            Boundary::Reflect if len == 1 => 0,
+            Boundary::Reflect => {
+                let period = 2 * (len - 1);
+                let m = c.rem_euclid(period);
+                if m < len {
+                    m
+                } else {
+                    period - m
+                }
+            }
+            Boundary::Absorb if (0..len).contains(&c) => c,
+            Boundary::Absorb => return None,
+        };
+        Some(mapped as i32)
+    }
+}
+
+/// Represents the way a [`WrappingCoords2d`] grid glues its opposite edges.
+/// See [Topologies](struct.WrappingCoords2d.html#topologies).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Both pairs of edges are glued without a twist. This is the default topology.
+    #[default]
+    Torus,
+    /// The left and right edges are glued with a twist: crossing them mirrors the y coordinate.
+    /// The top and bottom edges absorb.
+    MobiusStrip,
+    /// The left and right edges are glued with a twist, and the top and bottom edges are glued without a twist.
+    KleinBottle,
+    /// Both pairs of edges are glued with a twist: crossing the left and right edges mirrors the y coordinate,
+    /// and crossing the top and bottom edges mirrors the x coordinate.
+    ProjectivePlane,
+    /// Helical boundary conditions: crossing the right edge leads to the first cell of the next row, and crossing
+    /// the left edge leads to the last cell of the previous row. The top and bottom edges are glued without a twist,
+    /// so `shift(index, delta_x, delta_y)` is `index + delta_x + delta_y * width`, modulo the size of the grid.
+    Helical,
+    /// A torus whose top and bottom edges are glued with a shift: crossing the top edge upwards adds the given
+    /// value to the x coordinate, and crossing the bottom edge downwards subtracts it.
+    ShiftedTorus(i32),
+}
+
+impl Topology {
+    /// Returns `true` if crossing the left and right edges mirrors the y coordinate.
+    fn twists_x(self) -> bool {
+        matches!(
+            self,
+            Topology::MobiusStrip | Topology::KleinBottle | Topology::ProjectivePlane
+        )
+    }
+    /// Returns `true` if crossing the top and bottom edges mirrors the x coordinate.
+    fn twists_y(self) -> bool {
+        self == Topology::ProjectivePlane
+    }
+}
+
+/// Represents the rotation sense of a [`NeighborOrder`], as seen on the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// Counter-clockwise rotation. This is the default rotation.
+    #[default]
+    CounterClockwise,
+    /// Clockwise rotation.
+    Clockwise,
+}
+
+/// Represents the way the y axis of a grid is displayed on the screen; see [`NeighborOrder`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum YAxis {
+    /// y increases upwards, as in mathematics. This is the default.
+    #[default]
+    Up,
+    /// y increases downwards, as the rows of an image.
+    Down,
+}
+
+/// Represents the order of the neighbors returned by the neighbor functions of a [`WrappingCoords2d`] grid.
+/// See [Neighbor order](struct.WrappingCoords2d.html#neighbor-order).
+///
+/// Each ring of neighbors starts at the direction `start` and goes around the cell in the sense `rotation`,
+/// as seen on a screen where y increases in the direction `y_axis`. With `YAxis::Down`, `Direction::North` points to
+/// the offset `(0, -1)`, which is at the top of the screen. Rings without a cell in the direction `start` begin with the
+/// next cell in the sense `rotation`. The default order is counter-clockwise, starting from `Direction::East`,
+/// with `YAxis::Up`.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{Direction, NeighborOrder, Rotation, WrappingCoords2d, YAxis};
+/// // Clockwise from north, with y increasing upwards:
+/// let order = NeighborOrder {
+///     start: Direction::North,
+///     rotation: Rotation::Clockwise,
+///     ..NeighborOrder::default()
+/// };
+/// let w2d = WrappingCoords2d::new_with_order(10, 10, order).unwrap();
+/// assert_eq!(w2d.neighbors4(55), vec![65, 56, 45, 54]);
+/// // The second ring starts at the second cell above:
+/// assert_eq!(w2d.neighbors24(55)[8..11], [75, 76, 77]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NeighborOrder {
+    /// Direction of the first neighbor of each ring.
+    pub start: Direction,
+    /// Rotation sense of each ring.
+    pub rotation: Rotation,
+    /// Direction of the y axis on the screen.
+    pub y_axis: YAxis,
+}
+
+impl Default for NeighborOrder {
+    fn default() -> Self {
+        NeighborOrder {
+            start: Direction::East,
+            rotation: Rotation::CounterClockwise,
+            y_axis: YAxis::Up,
+        }
+    }
+}
+
+impl NeighborOrder {
+    /// Returns `true` if this is the default order, which the fast paths of this module follow.
+    fn is_default(&self) -> bool {
+        *self == NeighborOrder::default()
+    }
+    /// Compares two non-zero offsets by their angle from `start`, in the sense `rotation`, as seen on the screen.
+    fn compare(&self, a: (i32, i32), b: (i32, i32)) -> std::cmp::Ordering {
+        // Mirroring the y axis turns a clockwise order, or a downward y axis, into the counter-clockwise order of compare_angles
+        let clockwise = self.rotation == Rotation::Clockwise;
+        let mirror = clockwise != (self.y_axis == YAxis::Down);
+        let mirrored = |(dx, dy): (i32, i32)| if mirror { (dx, -dy) } else { (dx, dy) };
+        let (sx, sy) = self.start.offset();
+        let start = if clockwise { (sx, -sy) } else { (sx, sy) };
+        compare_angles(start, mirrored(a), mirrored(b))
+    }
+    /// Sorts each ring of `offsets`, that is, each run of offsets with the same value of `ring`, in this order.
+    /// The offsets must not include `(0, 0)`.
+    fn arrange(&self, offsets: &mut [(i32, i32)], ring: fn((i32, i32)) -> i64) {
+        if self.is_default() {
+            return;
+        }
+        self.sort_rings(offsets, ring);
+    }
+    /// Sorts each ring of `offsets` in this order, even if this is the default order.
+    fn sort_rings(&self, offsets: &mut [(i32, i32)], ring: fn((i32, i32)) -> i64) {
+        let mut start = 0;
+        while start < offsets.len() {
+            let length = ring(offsets[start]);
+            let end = offsets[start..]
+                .iter()
+                .position(|&offset| ring(offset) != length)
+                .map_or(offsets.len(), |run_length| start + run_length);
+            offsets[start..end].sort_by(|&a, &b| self.compare(a, b));
+            start = end;
+        }
+    }
+}
+
+/// Offsets `(dx, dy)` of the built-in neighborhoods of a grid, arranged in its [`NeighborOrder`].
+#[derive(Clone, Debug, PartialEq)]
+struct OrderedOffsets {
+    /// Offsets of the 4-neighborhood.
+    n4: [(i32, i32); 4],
+    /// Offsets of the 8-neighborhood.
+    n8: [(i32, i32); 8],
+    /// Offsets of the 16 second neighbors.
+    n16: [(i32, i32); 16],
+    /// Offsets of the 24 nearest neighbors: the 8-neighborhood followed by the 16 second neighbors.
+    n24: [(i32, i32); 24],
+}
+
+impl OrderedOffsets {
+    /// Arranges the built-in neighborhoods in `order`.
+    fn new(order: NeighborOrder) -> OrderedOffsets {
+        let (mut n4, mut n8, mut n16) = (OFFSETS4, OFFSETS8, OFFSETS16);
+        order.arrange(&mut n4, chebyshev_length);
+        order.arrange(&mut n8, chebyshev_length);
+        order.arrange(&mut n16, chebyshev_length);
+        let mut n24 = OFFSETS24;
+        n24[..8].copy_from_slice(&n8);
+        n24[8..].copy_from_slice(&n16);
+        OrderedOffsets { n4, n8, n16, n24 }
+    }
+}
+
+/// Reports the axes mirrored by a move across twisted edges; see [`WrappingCoords2d::shift_oriented`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flip {
+    /// `true` if the move mirrors the x axis.
+    pub x: bool,
+    /// `true` if the move mirrors the y axis.
+    pub y: bool,
+}
+
+impl Flip {
+    /// Returns `heading`, a direction `(dx, dy)` in grid coordinates, mirrored along the flipped axes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Flip;
+    /// assert_eq!(Flip { x: true, y: false }.apply((1, 2)), (-1, 2));
+    /// assert_eq!(Flip::default().apply((1, 2)), (1, 2));
+    /// ```
+    pub fn apply(self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        (if self.x { -dx } else { dx }, if self.y { -dy } else { dy })
+    }
+}
+
+/// Represents a user-defined neighborhood: a list of offsets `(dx, dy)` from a cell to its neighbors.
+/// Use [`WrappingCoords2d::stencil`] to construct a stencil, and [`WrappingCoords2d::for_each_stencil`]
+/// or [`WrappingCoords2d::neighbors_stencil`] to use it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stencil {
+    /// Offsets to the neighbors, in the order given by the user.
+    offsets: std::vec::Vec<(i32, i32)>,
+    /// Width of the grid used to compute `x_shifts`.
+    w32: i32,
+    /// Height of the grid used to compute `yw_shifts`.
+    h32: i32,
+    /// x offsets in the form (x + w) required by `WrappingCoords2d::for_each`.
+    x_shifts: std::vec::Vec<usize>,
+    /// y offsets in the form (yw + sz) required by `WrappingCoords2d::for_each`.
+    yw_shifts: std::vec::Vec<usize>,
+}
+
+impl Stencil {
+    /// Returns the offsets `(dx, dy)` of the stencil.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let stencil = w2d.stencil(&[(3, 0), (0, 3)]);
+    /// assert_eq!(stencil.offsets(), &[(3, 0), (0, 3)]);
+    /// ```
+    pub fn offsets(&self) -> &[(i32, i32)] {
+        &self.offsets
+    }
+    /// Returns the number of offsets in the stencil.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.stencil(&[(3, 0), (0, 3)]).len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+    /// Returns `true` if the stencil has no offsets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingCoords2d;
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert!(w2d.stencil(&[]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+}
+
+/// Selects one of the neighborhoods of a cell for [`WrappingCoords2d::neighbors_iter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The von Neumann neighborhood or 4-neighborhood, as returned by `neighbors4`.
+    N4,
+    /// The Moore neighborhood or 8-neighborhood, as returned by `neighbors8`.
+    N8,
+    /// The 16 second neighbors, as returned by `neighbors16`.
+    N16,
+    /// The 24 nearest neighbors, as returned by `neighbors24`.
+    N24,
+}
+
+impl Neighborhood {
+    /// Returns the offsets `(dx, dy)` of the neighborhood, taken from the arranged `offsets` of a grid.
+    fn offsets(self, offsets: &OrderedOffsets) -> &[(i32, i32)] {
+        match self {
+            Neighborhood::N4 => &offsets.n4,
+            Neighborhood::N8 => &offsets.n8,
+            Neighborhood::N16 => &offsets.n16,
+            Neighborhood::N24 => &offsets.n24,
+        }
+    }
+}
+
+/// Lazy iterator over the indices to the neighbors of a cell; see [`WrappingCoords2d::neighbors_iter`].
+#[derive(Clone, Debug)]
+pub struct Neighbors<'a> {
+    /// Grid of the cell.
+    w2d: &'a WrappingCoords2d,
+    /// x coordinate of the cell; always positive.
+    x: i32,
+    /// y coordinate of the cell; always positive.
+    y: i32,
+    /// Offsets of the neighbors that have not been visited yet.
+    offsets: std::slice::Iter<'a, (i32, i32)>,
+}
+
+impl Iterator for Neighbors<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if !self.w2d.is_torus() {
+            let (w2d, x, y) = (self.w2d, self.x, self.y);
+            return self
+                .offsets
+                .find_map(|&(dx, dy)| w2d.locate(x, y, dx.into(), dy.into()));
+        }
+        let &(dx, dy) = self.offsets.next()?;
+        let new_x = WrappingCoords2d::wrapping_add(self.x, dx, self.w2d.w32);
+        let new_yw = WrappingCoords2d::wrapping_add(self.y, dy, self.w2d.h32) * self.w2d.w32;
+        Some((new_yw + new_x) as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if !self.w2d.absorbs() {
+            return self.offsets.size_hint();
+        }
+        let len = self
+            .offsets
+            .clone()
+            .filter(|&&(dx, dy)| {
+                self.w2d
+                    .locate(self.x, self.y, dx.into(), dy.into())
+                    .is_some()
+            })
+            .count();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Neighbors<'_> {}
+
+impl std::iter::FusedIterator for Neighbors<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns grids of various shapes, from a single cell to 100 million cells.
+    fn test_grids() -> std::vec::Vec<WrappingCoords2d> {
+        vec![
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(20, 20).unwrap(),
+            WrappingCoords2d::new(100, 100).unwrap(),
+            WrappingCoords2d::new(21, 2).unwrap(),
+            WrappingCoords2d::new(1, 1).unwrap(),
+            WrappingCoords2d::new(10000, 10).unwrap(),
+            WrappingCoords2d::new(10000, 10000).unwrap(),
+            WrappingCoords2d::new(1, 10000000).unwrap(),
+        ]
+    }
+
+    /// Returns the indices of the cells of `g` at its corners, next to its edges, and in its middle.
+    fn edge_indices(g: &WrappingCoords2d) -> std::vec::Vec<usize> {
+        let near_edges = |n: i32| [0, 1, n / 2, n - 2, n - 1];
+        let mut indices = std::vec::Vec::new();
+        for &y in near_edges(g.height()).iter() {
+            for &x in near_edges(g.width()).iter() {
+                if 0 <= x && x < g.width() && 0 <= y && y < g.height() {
+                    indices.push(g.index(x, y));
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    #[test]
+    fn test() {
+        // Basic examples - Copy over to the documentation section
+        let w2d = WrappingCoords2d::new(10, 10).unwrap();
+        // Here are some basic coordinates:
+        assert_eq!(w2d.coords(0), (0, 0));
+        assert_eq!(w2d.coords(1), (1, 0));
+        assert_eq!(w2d.coords(10), (0, 1));
+        assert_eq!(w2d.coords(11), (1, 1));
+        assert_eq!(w2d.coords(90), (0, 9));
+        assert_eq!(w2d.coords(91), (1, 9));
+        // Here are the cell at (5, 9) and its 8 neighbors, counterclockwise, starting from the right neighbor:
+        assert_eq!(w2d.index(5, 9), 95);
+        assert_eq!(w2d.index(6, 9), 96);
+        assert_eq!(w2d.index(6, 0), 6);
+        assert_eq!(w2d.index(5, 0), 5);
+        assert_eq!(w2d.index(4, 0), 4);
+        assert_eq!(w2d.index(4, 9), 94);
+        assert_eq!(w2d.index(4, 8), 84);
+        assert_eq!(w2d.index(5, 8), 85);
+        assert_eq!(w2d.index(6, 8), 86);
+        // Here are the cell at (0, 0) and its 8 neighbors, counterclockwise, starting from the right neighbor:
+        assert_eq!(w2d.index(0, 0), 0);
+        assert_eq!(w2d.index(1, 0), 1);
+        assert_eq!(w2d.index(1, 1), 11);
+        assert_eq!(w2d.index(0, 1), 10);
+        assert_eq!(w2d.index(-1, 1), 19);
+        assert_eq!(w2d.index(-1, 0), 9);
+        assert_eq!(w2d.index(-1, -1), 99);
+        assert_eq!(w2d.index(0, -1), 90);
+        assert_eq!(w2d.index(1, -1), 91);
+        // Here are the 8 neighbors of the cell at (5, 9), counterclockwise, starting from the right neighbor:
+        assert_eq!(w2d.shift(95, 1, 0), 96);
+        assert_eq!(w2d.shift(95, 1, 1), 6);
+        assert_eq!(w2d.shift(95, 0, 1), 5);
+        assert_eq!(w2d.shift(95, -1, 1), 4);
+        assert_eq!(w2d.shift(95, -1, 0), 94);
+        assert_eq!(w2d.shift(95, -1, -1), 84);
+        assert_eq!(w2d.shift(95, 0, -1), 85);
+        assert_eq!(w2d.shift(95, 1, -1), 86);
+        // Here are the 8 neighbors of the cell at (0, 0), counterclockwise, starting from the right neighbor:
+        assert_eq!(w2d.shift(0, 1, 0), 1);
+        assert_eq!(w2d.shift(0, 1, 1), 11);
+        assert_eq!(w2d.shift(0, 0, 1), 10);
+        assert_eq!(w2d.shift(0, -1, 1), 19);
+        assert_eq!(w2d.shift(0, -1, 0), 9);
+        assert_eq!(w2d.shift(0, -1, -1), 99);
+        assert_eq!(w2d.shift(0, 0, -1), 90);
+        assert_eq!(w2d.shift(0, 1, -1), 91);
+
+        // End of basic examples
+        // Easy-to-read tests; may repeat some basic examples
+        assert_eq!(w2d.shift(0, 1, 0), 1);
+        assert_eq!(w2d.shift(0, 11, 0), 1);
+        assert_eq!(w2d.shift(0, -1, 0), 9);
+        assert_eq!(w2d.shift(0, -11, 0), 9);
+        assert_eq!(w2d.shift(10, 1, 0), 11);
+        assert_eq!(w2d.shift(10, 11, 0), 11);
+        assert_eq!(w2d.shift(10, -1, 0), 19);
+        assert_eq!(w2d.shift(10, -11, 0), 19);
+        assert_eq!(w2d.shift(0, 0, 1), 10);
+        assert_eq!(w2d.shift(0, 0, 11), 10);
+        assert_eq!(w2d.shift(0, 0, -1), 90);
+        assert_eq!(w2d.shift(0, 0, -11), 90);
+        assert_eq!(w2d.shift(10, 0, 1), 20);
+        assert_eq!(w2d.shift(10, 0, 11), 20);
+        assert_eq!(w2d.shift(10, 0, -1), 0);
+        assert_eq!(w2d.shift(10, 0, -11), 0);
+
+        // More tests
+        for g in test_grids() {
+            assert_eq!(g.shift(0, 1, 0), 1 % g.wu);
+            assert_eq!(g.shift(0, 1, 20), (20 * g.wu + (1 % g.wu)) % g.szu);
+            assert_eq!(g.shift(0, 20, 20), (20 * g.wu + (20 % g.wu)) % g.szu);
+            assert_eq!(
+                g.shift(0, 200000, 200000),
+                (200000 * g.wu + (200000 % g.wu)) % g.szu
+            );
+            let x1 = 10000;
+            let y1 = 10000;
+            let idx1 = g.index(x1, y1);
+            assert_eq!(g.shift(idx1, 1, 0), g.index(x1 + 1, y1));
+            assert_eq!(g.shift(idx1, 1, 20), g.index(x1 + 1, y1 + 20));
+            assert_eq!(g.shift(idx1, 20, 20), g.index(x1 + 20, y1 + 20));
+            assert_eq!(
+                g.shift(idx1, 200000, 200000),
                 g.index(x1 + 200000, y1 + 200000)
             );
             assert_eq!(g.shift(idx1, -1, 0), g.index(x1 - 1, y1));
@@ -1078,4 +3413,1090 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn coords_round_trip() {
+        for g in test_grids() {
+            // Every cell of the small grids, and the cells near the edges of the large ones
+            let indices = if g.size() <= 1_000_000 {
+                (0..g.size()).collect()
+            } else {
+                edge_indices(&g)
+            };
+            for idx in indices {
+                let (x, y) = g.coords(idx);
+                assert!(0 <= x && x < g.width());
+                assert!(0 <= y && y < g.height());
+                assert_eq!(g.index(x, y), idx);
+            }
+            assert_eq!(
+                g.coords_checked(g.size() - 1),
+                Some((g.width() - 1, g.height() - 1))
+            );
+            assert_eq!(g.coords_checked(g.size()), None);
+        }
+    }
+
+    #[test]
+    fn small_grids() {
+        let grids = vec![
+            WrappingCoords2d::new(1, 1).unwrap(),
+            WrappingCoords2d::new(1, 7).unwrap(),
+            WrappingCoords2d::new(7, 1).unwrap(),
+            WrappingCoords2d::new(2, 2).unwrap(),
+            WrappingCoords2d::new(2, 5).unwrap(),
+            WrappingCoords2d::new(3, 3).unwrap(),
+            WrappingCoords2d::new(4, 4).unwrap(),
+        ];
+        for g in grids {
+            // Each neighbor is the result of `shift`, even when it repeats or matches the cell itself
+            let expected = |idx: usize, offsets: &[(i32, i32)]| -> std::vec::Vec<usize> {
+                offsets
+                    .iter()
+                    .map(|&(dx, dy)| g.shift(idx, dx, dy))
+                    .collect()
+            };
+            for idx in 0..g.size() {
+                assert_eq!(g.neighbors4(idx), expected(idx, &OFFSETS4));
+                assert_eq!(g.neighbors8(idx), expected(idx, &OFFSETS8));
+                assert_eq!(g.neighbors16(idx), expected(idx, &OFFSETS16));
+                assert_eq!(g.neighbors24(idx), expected(idx, &OFFSETS24));
+            }
+            let mut calls_counter = 0;
+            g.for_each4(|idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors4_array(idx));
+                calls_counter += 1;
+            });
+            g.for_each8(|idx, neighbors| assert_eq!(*neighbors, g.neighbors8_array(idx)));
+            g.for_each16(|idx, neighbors| assert_eq!(*neighbors, g.neighbors16_array(idx)));
+            g.for_each24(|idx, neighbors| assert_eq!(*neighbors, g.neighbors24_array(idx)));
+            assert_eq!(calls_counter, g.size());
+        }
+        // The only cell of a 1x1 grid is its own neighbor
+        let g = WrappingCoords2d::new(1, 1).unwrap();
+        assert_eq!(g.neighbors8(0), vec![0; 8]);
+        assert_eq!(g.neighbors24(0), vec![0; 24]);
+        // In a 1xN grid, the left and right neighbors are the cell itself
+        let g = WrappingCoords2d::new(1, 7).unwrap();
+        assert_eq!(g.neighbors4(3), vec![3, 4, 3, 2]);
+        // In a 2x2 grid, the left and right neighbors coincide
+        let g = WrappingCoords2d::new(2, 2).unwrap();
+        assert_eq!(g.neighbors4(0), vec![1, 2, 1, 2]);
+        assert_eq!(g.neighbors8(0), vec![1, 3, 2, 3, 1, 3, 2, 3]);
+        // In a 3x3 grid, the 8 neighbors are distinct, but the second neighbors wrap onto the first ones
+        let g = WrappingCoords2d::new(3, 3).unwrap();
+        let mut neighbors8 = g.neighbors8(4);
+        neighbors8.sort_unstable();
+        assert_eq!(neighbors8, vec![0, 1, 2, 3, 5, 6, 7, 8]);
+        assert_eq!(g.neighbors16(4)[0], g.neighbors8(4)[4]);
+        // `new_with_radius` rules out grids where neighbors repeat
+        for &(w, h) in [(1, 1), (1, 7), (7, 1), (2, 2), (2, 5)].iter() {
+            assert!(matches!(
+                WrappingCoords2d::new_with_radius(w, h, 1),
+                Err(ErrorKind::GridTooSmallForNeighborhood)
+            ));
+        }
+        assert!(WrappingCoords2d::new_with_radius(1, 1, 0).is_ok());
+        assert!(WrappingCoords2d::new_with_radius(3, 3, 1).is_ok());
+        assert!(matches!(
+            WrappingCoords2d::new_with_radius(3, 3, 2),
+            Err(ErrorKind::GridTooSmallForNeighborhood)
+        ));
+        assert!(matches!(
+            WrappingCoords2d::new_with_radius(4, 5, 2),
+            Err(ErrorKind::GridTooSmallForNeighborhood)
+        ));
+        assert!(WrappingCoords2d::new_with_radius(5, 5, 2).is_ok());
+        assert!(matches!(
+            WrappingCoords2d::new_with_radius(5, 5, u32::MAX),
+            Err(ErrorKind::GridTooSmallForNeighborhood)
+        ));
+    }
+
+    #[test]
+    fn neighbors_iter() {
+        let grids = vec![
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(21, 2).unwrap(),
+            WrappingCoords2d::new(1, 1).unwrap(),
+            WrappingCoords2d::new(3, 3).unwrap(),
+        ];
+        for g in grids {
+            for idx in 0..g.size() {
+                let mut iter = g.neighbors_iter(idx, Neighborhood::N4);
+                assert_eq!(iter.len(), 4);
+                iter.next();
+                assert_eq!(iter.len(), 3);
+                let collect = |neighborhood| -> std::vec::Vec<usize> {
+                    g.neighbors_iter(idx, neighborhood).collect()
+                };
+                assert_eq!(collect(Neighborhood::N4), g.neighbors4(idx));
+                assert_eq!(collect(Neighborhood::N8), g.neighbors8(idx));
+                assert_eq!(collect(Neighborhood::N16), g.neighbors16(idx));
+                assert_eq!(collect(Neighborhood::N24), g.neighbors24(idx));
+            }
+            let neighbors: std::vec::Vec<usize> = g
+                .neighbors_iterxy(i32::MIN, i32::MAX, Neighborhood::N24)
+                .collect();
+            assert_eq!(neighbors, g.neighbors24xy(i32::MIN, i32::MAX));
+        }
+    }
+
+    #[test]
+    fn radius_neighborhoods() {
+        assert!(moore_offsets(0).is_empty());
+        assert!(von_neumann_offsets(0).is_empty());
+        assert_eq!(moore_offsets(1), OFFSETS8);
+        assert_eq!(moore_offsets(2), OFFSETS24);
+        assert_eq!(von_neumann_offsets(1), OFFSETS4);
+        for r in 0..6 {
+            let moore = moore_offsets(r);
+            let von_neumann = von_neumann_offsets(r);
+            let r = r as i32;
+            assert_eq!(moore.len() as i32, (2 * r + 1) * (2 * r + 1) - 1);
+            assert_eq!(von_neumann.len() as i32, 2 * r * (r + 1));
+            assert!(von_neumann.iter().all(|&(dx, dy)| dx.abs() + dy.abs() <= r));
+            let chebyshev = |&(dx, dy): &(i32, i32)| dx.abs().max(dy.abs());
+            let manhattan = |&(dx, dy): &(i32, i32)| dx.abs() + dy.abs();
+            for (offsets, ring) in [
+                (moore, chebyshev as fn(&(i32, i32)) -> i32),
+                (von_neumann, manhattan),
+            ]
+            .iter()
+            {
+                // Every offset appears once
+                let mut sorted = offsets.clone();
+                sorted.sort_unstable();
+                sorted.dedup();
+                assert_eq!(sorted.len(), offsets.len());
+                // Rings are ordered from the closest one, and each ring is ordered counter-clockwise, starting from the right
+                let keys: std::vec::Vec<(i32, f64)> = offsets
+                    .iter()
+                    .map(|offset| {
+                        let angle = (offset.1 as f64).atan2(offset.0 as f64);
+                        (ring(offset), angle.rem_euclid(2.0 * std::f64::consts::PI))
+                    })
+                    .collect();
+                assert!(keys.windows(2).all(|k| k[0] < k[1]));
+            }
+        }
+        for g in [
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(21, 2).unwrap(),
+            WrappingCoords2d::new(1, 1).unwrap(),
+        ]
+        .iter()
+        {
+            g.for_each_moore(1, |idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors8(idx)[..])
+            });
+            g.for_each_moore(2, |idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors24(idx)[..])
+            });
+            g.for_each_von_neumann(1, |idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors4(idx)[..])
+            });
+            g.for_each_moore(4, |idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors_moore(idx, 4)[..])
+            });
+            g.for_each_von_neumann(4, |idx, neighbors| {
+                assert_eq!(*neighbors, g.neighbors_von_neumann(idx, 4)[..])
+            });
+        }
+        // Huge radii are reduced to width + height
+        let g = WrappingCoords2d::new(3, 2).unwrap();
+        for &radius in [5, 6, 40000, u32::MAX].iter() {
+            assert_eq!(g.neighbors_moore(0, radius), g.neighbors_moore(0, 5));
+            assert_eq!(
+                g.neighbors_von_neumann(0, radius),
+                g.neighbors_von_neumann(0, 5)
+            );
+        }
+        let mut sorted = g.neighbors_moore(0, u32::MAX);
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, (0..g.size()).collect::<std::vec::Vec<_>>());
+        let g = WrappingCoords2d::new_with_boundaries(3, 2, Boundary::Absorb, Boundary::Absorb)
+            .unwrap();
+        assert_eq!(g.neighbors_von_neumann(0, u32::MAX).len(), 5);
+        g.for_each_moore(u32::MAX, |_, neighbors| assert_eq!(neighbors.len(), 5));
+    }
+
+    #[test]
+    fn stencils() {
+        let offsets = [(2, 1), (0, 0), (-7, 3), (2, 1), (i32::MAX, i32::MIN)];
+        let g10 = WrappingCoords2d::new(10, 10).unwrap();
+        let stencil = g10.stencil(&offsets);
+        for g in [
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(21, 2).unwrap(),
+            WrappingCoords2d::new(1, 1).unwrap(),
+            WrappingCoords2d::new(3, 5).unwrap(),
+        ]
+        .iter()
+        {
+            let mut calls_counter = 0;
+            // The stencil made for the 10x10 grid also works on other grids
+            g.for_each_stencil(&stencil, |idx, neighbors| {
+                let expected: std::vec::Vec<usize> = offsets
+                    .iter()
+                    .map(|&(dx, dy)| g.shift(idx, dx, dy))
+                    .collect();
+                assert_eq!(*neighbors, expected[..]);
+                assert_eq!(g.neighbors_stencil(idx, &stencil), expected);
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, g.size());
+        }
+        g10.for_each_stencil(&g10.stencil(&[]), |_, neighbors| {
+            assert!(neighbors.is_empty())
+        });
+    }
+
+    #[test]
+    fn disks_and_rings() {
+        let unlimited = (i32::MAX, i32::MAX);
+        assert!(ring_offsets(0.0, 0.0, unlimited).is_empty());
+        assert!(ring_offsets(0.0, -1.0, unlimited).is_empty());
+        assert!(ring_offsets(0.0, f64::NAN, unlimited).is_empty());
+        assert_eq!(ring_offsets(0.0, 1.0, unlimited), OFFSETS4);
+        assert_eq!(ring_offsets(-5.0, 1.0, unlimited), OFFSETS4);
+        assert_eq!(ring_offsets(0.0, f64::INFINITY, (1, 0)), [(1, 0), (-1, 0)]);
+        assert_eq!(
+            ring_offsets(1.1, 1.5, unlimited),
+            vec![(1, 1), (-1, 1), (-1, -1), (1, -1)]
+        );
+        for &r in [0.5, 1.0, 2.0, 2.9, 7.3].iter() {
+            let offsets = ring_offsets(0.0, r, unlimited);
+            let expected = (-8..=8)
+                .flat_map(|dy| (-8..=8).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy): &(i32, i32)| {
+                    (dx, dy) != (0, 0) && ((dx * dx + dy * dy) as f64).sqrt() <= r
+                })
+                .count();
+            assert_eq!(offsets.len(), expected);
+            let keys: std::vec::Vec<(i64, f64)> = offsets
+                .iter()
+                .map(|&(dx, dy)| {
+                    let angle = (dy as f64).atan2(dx as f64);
+                    (
+                        squared_length((dx, dy)),
+                        angle.rem_euclid(2.0 * std::f64::consts::PI),
+                    )
+                })
+                .collect();
+            assert!(keys.windows(2).all(|k| k[0] < k[1]));
+        }
+        // Distances are the lengths of the shortest displacements, so each cell appears once
+        for g in [
+            WrappingCoords2d::new(21, 2).unwrap(),
+            WrappingCoords2d::new(4, 3).unwrap(),
+            WrappingCoords2d::new(1, 1).unwrap(),
+            WrappingCoords2d::new_with_boundaries(5, 4, Boundary::Clamp, Boundary::Absorb).unwrap(),
+            WrappingCoords2d::new_with_topology(5, 4, Topology::MobiusStrip).unwrap(),
+            WrappingCoords2d::new_with_topology(4, 5, Topology::KleinBottle).unwrap(),
+            WrappingCoords2d::new_with_topology(3, 4, Topology::ProjectivePlane).unwrap(),
+            WrappingCoords2d::new_with_topology(6, 3, Topology::Helical).unwrap(),
+            WrappingCoords2d::new_with_topology(3, 6, Topology::ShiftedTorus(2)).unwrap(),
+        ]
+        .iter()
+        {
+            for &(inner, outer) in
+                [(0.0, 3.0), (1.5, 3.0), (0.0, 1e12), (0.0, f64::INFINITY)].iter()
+            {
+                for idx in 0..g.size() {
+                    let mut expected: std::vec::Vec<usize> = (0..g.size())
+                        .filter(|&other| {
+                            let d = (squared_length(g.displacement(idx, other)) as f64).sqrt();
+                            other != idx && inner <= d && d <= outer
+                        })
+                        .collect();
+                    let mut sorted = g.neighbors_ring(idx, inner, outer);
+                    sorted.sort_unstable();
+                    expected.sort_unstable();
+                    assert_eq!(sorted, expected);
+                }
+                g.for_each_ring(inner, outer, |idx, neighbors| {
+                    assert_eq!(*neighbors, g.neighbors_ring(idx, inner, outer)[..])
+                });
+            }
+        }
+        let g = WrappingCoords2d::new(21, 2).unwrap();
+        assert_eq!(g.neighbors_disk(0, 3.0).len(), 11);
+        assert_eq!(g.neighbors_disk(0, f64::INFINITY).len(), g.size() - 1);
+        g.for_each_disk(3.0, |idx, neighbors| {
+            assert_eq!(*neighbors, g.neighbors_disk(idx, 3.0)[..])
+        });
+    }
+
+    #[test]
+    fn edges() {
+        // Reference implementation: the set of unordered pairs of distinct cells within a set of offsets
+        let reference = |g: &WrappingCoords2d, offsets: &[(i32, i32)]| {
+            let mut edges = std::collections::BTreeSet::new();
+            for idx in 0..g.size() {
+                for &(dx, dy) in offsets.iter() {
+                    let neighbor = g.shift(idx, dx, dy);
+                    if neighbor != idx {
+                        edges.insert((idx.min(neighbor), idx.max(neighbor)));
+                    }
+                }
+            }
+            edges
+        };
+        for (w, h) in [
+            (10, 10),
+            (21, 2),
+            (1, 1),
+            (1, 7),
+            (7, 1),
+            (2, 2),
+            (2, 5),
+            (3, 3),
+        ]
+        .iter()
+        {
+            let g = WrappingCoords2d::new(*w, *h).unwrap();
+            let mut edges4 = std::vec::Vec::new();
+            g.for_each_edge4(|a, b| edges4.push((a, b)));
+            let mut edges8 = std::vec::Vec::new();
+            g.for_each_edge8(|a, b| edges8.push((a, b)));
+            let knight = g.stencil(&[(2, 1), (-1, 2), (0, 0), (i32::MIN, 1)]);
+            let mut edges_knight = std::vec::Vec::new();
+            g.for_each_edge_stencil(&knight, |a, b| edges_knight.push((a, b)));
+            for (edges, offsets) in [
+                (edges4, OFFSETS4.to_vec()),
+                (edges8, OFFSETS8.to_vec()),
+                (edges_knight, vec![(2, 1), (-1, 2), (0, 0), (i32::MIN, 1)]),
+            ]
+            .iter()
+            {
+                let expected = reference(&g, offsets);
+                assert_eq!(edges.len(), expected.len());
+                assert!(edges
+                    .iter()
+                    .all(|edge| edge.0 < edge.1 && expected.contains(edge)));
+            }
+        }
+        // Wrapping in a 1xN grid makes a cell its own neighbor to the right and left
+        let g = WrappingCoords2d::new(1, 7).unwrap();
+        let mut edges_counter = 0;
+        g.for_each_edge4(|_, _| edges_counter += 1);
+        assert_eq!(edges_counter, 7);
+    }
+
+    #[test]
+    fn distances() {
+        for (w, h) in [(10, 10), (21, 2), (1, 1), (1, 7), (2, 2), (3, 4), (7, 5)].iter() {
+            let g = WrappingCoords2d::new(*w, *h).unwrap();
+            for a in 0..g.size() {
+                for b in 0..g.size() {
+                    let (dx, dy) = g.displacement(a, b);
+                    assert_eq!(g.shift(a, dx, dy), b);
+                    // Each component is the shortest one, preferring the positive direction on ties
+                    assert!(-w < 2 * dx && 2 * dx <= *w);
+                    assert!(-h < 2 * dy && 2 * dy <= *h);
+                    assert_eq!(g.displacement(b, a).0 == -dx, 2 * dx != *w);
+                    assert_eq!(g.manhattan_distance(a, b), dx.abs() + dy.abs());
+                    assert_eq!(g.manhattan_distance(a, b), g.manhattan_distance(b, a));
+                    assert_eq!(g.chebyshev_distance(a, b), g.chebyshev_distance(b, a));
+                    assert_eq!(g.squared_distance(a, b), g.squared_distance(b, a));
+                    // The minimal image is the closest one among the neighboring images
+                    let (xa, ya) = g.coords(a);
+                    let (xb, yb) = g.coords(b);
+                    let closest = (-1..=1)
+                        .flat_map(|i| (-1..=1).map(move |j| (i, j)))
+                        .map(|(i, j)| squared_length((xb + i * w - xa, yb + j * h - ya)))
+                        .min()
+                        .unwrap();
+                    assert_eq!(g.squared_distance(a, b), closest);
+                }
+            }
+        }
+        let g = WrappingCoords2d::new(i32::MAX, 1).unwrap();
+        assert_eq!(g.displacement(0, g.size() - 1), (-1, 0));
+        assert_eq!(g.displacement(0, g.size() / 2), (i32::MAX / 2, 0));
+        assert_eq!(g.displacement(0, g.size() / 2 + 1), (-(i32::MAX / 2), 0));
+    }
+
+    #[test]
+    fn boundaries() {
+        // Reference: move one cell at a time, turning back at the edges
+        fn walk(c: i32, delta: i32, len: i32, boundary: Boundary) -> Option<i32> {
+            let mut c = c;
+            let mut step = delta.signum();
+            for _ in 0..delta.abs() {
+                if !(0..len).contains(&(c + step)) {
+                    match boundary {
+                        Boundary::Wrap => c -= step * len,
+                        Boundary::Clamp => continue,
+                        Boundary::Reflect if len == 1 => continue,
+                        Boundary::Reflect => step = -step,
+                        Boundary::Absorb => return None,
+                    }
+                }
+                c += step;
+            }
+            Some(c)
+        }
+        let policies = [
+            Boundary::Wrap,
+            Boundary::Clamp,
+            Boundary::Reflect,
+            Boundary::Absorb,
+        ];
+        let knight = [(2, 1), (1, 2), (-1, 2), (-2, 1)];
+        for (w, h) in [(10, 10), (1, 1), (2, 3), (5, 4), (1, 6)].iter() {
+            for &bx in policies.iter() {
+                for &by in policies.iter() {
+                    let g = WrappingCoords2d::new_with_boundaries(*w, *h, bx, by).unwrap();
+                    assert_eq!((g.x_boundary(), g.y_boundary()), (bx, by));
+                    let absorbs = bx == Boundary::Absorb || by == Boundary::Absorb;
+                    for a in 0..g.size() {
+                        let (x, y) = g.coords(a);
+                        for dx in -12..=12 {
+                            for dy in -12..=12 {
+                                let expected = walk(x, dx, *w, bx)
+                                    .and_then(|nx| walk(y, dy, *h, by).map(|ny| g.index(nx, ny)));
+                                assert_eq!(g.shift_checked(a, dx, dy), expected);
+                                assert_eq!(g.index_checked(x + dx, y + dy), expected);
+                                if let Some(index) = expected {
+                                    assert_eq!(g.shift(a, dx, dy), index);
+                                    assert_eq!(g.shift64(a, dx as i64, dy as i64), index);
+                                    assert_eq!(g.index64((x + dx) as i64, (y + dy) as i64), index);
+                                }
+                            }
+                        }
+                        let neighbors8: std::vec::Vec<usize> = OFFSETS8
+                            .iter()
+                            .filter_map(|&(dx, dy)| g.shift_checked(a, dx, dy))
+                            .collect();
+                        assert_eq!(g.neighbors8(a), neighbors8);
+                        let iter = g.neighbors_iter(a, Neighborhood::N8);
+                        assert_eq!(iter.len(), neighbors8.len());
+                        assert_eq!(iter.collect::<std::vec::Vec<usize>>(), neighbors8);
+                        if !absorbs {
+                            assert_eq!(g.neighbors24_array(a).to_vec(), g.neighbors24(a));
+                        }
+                    }
+                    let mut calls_counter = 0;
+                    g.for_each_moore(2, |this_cell_index, neighbors| {
+                        assert_eq!(neighbors, &g.neighbors_moore(this_cell_index, 2)[..]);
+                        calls_counter += 1;
+                    });
+                    assert_eq!(calls_counter, g.size());
+                    if !absorbs {
+                        g.for_each16(|this_cell_index, neighbors| {
+                            assert_eq!(neighbors, &g.neighbors16_array(this_cell_index));
+                        });
+                    }
+                    g.for_each16_checked(|this_cell_index, neighbors| {
+                        let present: std::vec::Vec<usize> =
+                            neighbors.iter().flatten().copied().collect();
+                        assert_eq!(present, g.neighbors16(this_cell_index));
+                    });
+                    let mut pairs = std::vec::Vec::new();
+                    g.for_each_pair4(|a, b| pairs.push((a, b)));
+                    let expected: std::vec::Vec<(usize, usize)> = (0..g.size())
+                        .flat_map(|a| g.neighbors4(a).into_iter().map(move |b| (a, b)))
+                        .collect();
+                    assert_eq!(pairs, expected);
+                    // Each undirected edge is visited once, whichever end reaches the other
+                    let mut edges = std::vec::Vec::new();
+                    g.for_each_edge_stencil(&g.stencil(&knight), |a, b| edges.push((a, b)));
+                    let mut expected = std::vec::Vec::new();
+                    for a in 0..g.size() {
+                        for b in g.neighbors_stencil(a, &g.stencil(&knight)) {
+                            if a != b {
+                                expected.push((a.min(b), a.max(b)));
+                            }
+                        }
+                    }
+                    expected.sort_unstable();
+                    expected.dedup();
+                    edges.sort_unstable();
+                    assert_eq!(edges, expected);
+                }
+            }
+        }
+        // A cylinder wraps east-west and absorbs north-south
+        let g = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb)
+            .unwrap();
+        assert_eq!(g.neighbors4(5), vec![6, 15, 4]);
+        assert_eq!(g.neighbors_iter(95, Neighborhood::N24).len(), 14);
+        let present = |neighbors: &[Option<usize>]| -> std::vec::Vec<usize> {
+            neighbors.iter().flatten().copied().collect()
+        };
+        let mut calls_counter = 0;
+        g.for_each4_checked(|idx, neighbors| {
+            assert_eq!(present(neighbors), g.neighbors4(idx));
+            calls_counter += 1;
+        });
+        g.for_each8_checked(|idx, neighbors| assert_eq!(present(neighbors), g.neighbors8(idx)));
+        g.for_each16_checked(|idx, neighbors| assert_eq!(present(neighbors), g.neighbors16(idx)));
+        g.for_each24_checked(|idx, neighbors| {
+            assert_eq!(present(neighbors), g.neighbors24(idx));
+            assert_eq!(
+                present(neighbors).len(),
+                5 * [3, 4, 5, 5, 5, 5, 5, 5, 4, 3][idx / 10] - 1
+            );
+        });
+        assert_eq!(calls_counter, g.size());
+        let mut edges_counter = 0;
+        g.for_each_edge4(|_, _| edges_counter += 1);
+        assert_eq!(edges_counter, 190);
+        let mut edges_counter = 0;
+        g.for_each_edge8(|_, _| edges_counter += 1);
+        assert_eq!(edges_counter, 370);
+        assert_eq!(g.displacement(0, 99), (-1, 9));
+        assert_eq!(g.manhattan_distance(90, 9), 10);
+        // Clamped and reflected coordinates can take any value in the range of `i32`
+        let g = WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Clamp, Boundary::Reflect)
+            .unwrap();
+        assert_eq!(g.index(i32::MIN, 10), 80);
+        assert_eq!(g.index(i32::MAX, i32::MAX), g.index(9, i32::MAX % 18));
+        assert_eq!(
+            g.shift(99, i32::MAX, i32::MIN),
+            g.index(9, (9 + i32::MIN as i64).rem_euclid(18) as i32)
+        );
+        assert_eq!(g.shift64(0, i64::MIN, 18), 0);
+    }
+
+    #[test]
+    fn topologies() {
+        // Reference: move one cell at a time, mirroring the other axis across twisted edges
+        fn walk(
+            g: &WrappingCoords2d,
+            start_index: usize,
+            dx: i32,
+            dy: i32,
+        ) -> Option<(usize, Flip)> {
+            let (w, h) = (g.width(), g.height());
+            let (mut x, mut y) = g.coords(start_index);
+            let mut flip = Flip::default();
+            let mut step_y = dy.signum();
+            for _ in 0..dx.abs() {
+                x += dx.signum();
+                if !(0..w).contains(&x) {
+                    x = WrappingCoords2d::modulo(x, w);
+                    if g.topology() != Topology::Torus {
+                        y = h - 1 - y;
+                        step_y = -step_y;
+                        flip.y = !flip.y;
+                    }
+                }
+            }
+            for _ in 0..dy.abs() {
+                y += step_y;
+                if !(0..h).contains(&y) {
+                    if g.topology() == Topology::MobiusStrip {
+                        return None;
+                    }
+                    y = WrappingCoords2d::modulo(y, h);
+                    if g.topology() == Topology::ProjectivePlane {
+                        x = w - 1 - x;
+                        flip.x = !flip.x;
+                    }
+                }
+            }
+            Some(((y * w + x) as usize, flip))
+        }
+        let topologies = [
+            Topology::Torus,
+            Topology::MobiusStrip,
+            Topology::KleinBottle,
+            Topology::ProjectivePlane,
+        ];
+        let knight = [(2, 1), (1, 2), (-1, 2), (-2, 1)];
+        for (w, h) in [(10, 10), (1, 1), (2, 3), (5, 4), (3, 1)].iter() {
+            for &topology in topologies.iter() {
+                let g = WrappingCoords2d::new_with_topology(*w, *h, topology).unwrap();
+                assert_eq!(g.topology(), topology);
+                for a in 0..g.size() {
+                    let (x, y) = g.coords(a);
+                    for dx in -12..=12 {
+                        for dy in -12..=12 {
+                            let expected = walk(&g, a, dx, dy);
+                            assert_eq!(g.shift_oriented_checked(a, dx, dy), expected);
+                            assert_eq!(g.shift_checked(a, dx, dy), expected.map(|(i, _)| i));
+                            if let Some((index, _)) = expected {
+                                assert_eq!(g.shift64(a, dx as i64, dy as i64), index);
+                                if (x, y) == (0, 0) {
+                                    assert_eq!(g.index(dx, dy), index);
+                                    assert_eq!(g.index64(dx as i64, dy as i64), index);
+                                }
+                            }
+                        }
+                    }
+                    // A step back, in the mirrored direction, returns to the cell
+                    for &(dx, dy) in OFFSETS4.iter() {
+                        if let Some((b, flip)) = g.shift_oriented_checked(a, dx, dy) {
+                            let (back_x, back_y) = flip.apply((-dx, -dy));
+                            assert_eq!(g.shift(b, back_x, back_y), a);
+                        }
+                    }
+                    let oriented: std::vec::Vec<usize> = g
+                        .neighbors_oriented(a, Neighborhood::N8)
+                        .into_iter()
+                        .map(|(index, _)| index)
+                        .collect();
+                    assert_eq!(g.neighbors8(a), oriented);
+                    assert_eq!(
+                        g.neighbors_iter(a, Neighborhood::N8)
+                            .collect::<std::vec::Vec<usize>>(),
+                        oriented
+                    );
+                    // The displacement is the shortest move that reaches the cell
+                    for b in 0..g.size() {
+                        let (dx, dy) = g.displacement(a, b);
+                        assert_eq!(g.shift_checked(a, dx, dy), Some(b));
+                        let shortest = (-w..=*w)
+                            .flat_map(|dx| (-h..=*h).map(move |dy| (dx, dy)))
+                            .filter(|&(dx, dy)| g.shift_checked(a, dx, dy) == Some(b))
+                            .map(squared_length)
+                            .min();
+                        assert_eq!(Some(g.squared_distance(a, b)), shortest);
+                    }
+                }
+                if topology != Topology::MobiusStrip {
+                    g.for_each24(|this_cell_index, neighbors| {
+                        assert_eq!(neighbors, &g.neighbors24_array(this_cell_index));
+                    });
+                }
+                g.for_each24_checked(|this_cell_index, neighbors| {
+                    let present: std::vec::Vec<usize> =
+                        neighbors.iter().flatten().copied().collect();
+                    assert_eq!(present, g.neighbors24(this_cell_index));
+                });
+                g.for_each_stencil(&g.stencil(&knight), |this_cell_index, neighbors| {
+                    assert_eq!(
+                        neighbors,
+                        &g.neighbors_stencil(this_cell_index, &g.stencil(&knight))[..]
+                    );
+                });
+                let mut edges = std::vec::Vec::new();
+                g.for_each_edge8(|a, b| edges.push((a, b)));
+                let mut expected = std::vec::Vec::new();
+                for a in 0..g.size() {
+                    for b in g.neighbors8(a) {
+                        if a != b {
+                            expected.push((a.min(b), a.max(b)));
+                        }
+                    }
+                }
+                expected.sort_unstable();
+                expected.dedup();
+                edges.sort_unstable();
+                assert_eq!(edges, expected);
+            }
+        }
+        // Offsets can take any value in the range of `i32`
+        let g = WrappingCoords2d::new_with_topology(10, 10, Topology::ProjectivePlane).unwrap();
+        let (index, flip) = g.shift_oriented(0, i32::MAX, i32::MIN);
+        // i32::MAX = 10 * 214748364 + 7 and i32::MIN = 10 * -214748365 + 2
+        assert_eq!(flip, Flip { x: true, y: false });
+        assert_eq!(index, g.index(9 - 7, 2));
+    }
+
+    #[test]
+    fn helical_and_shifted_tori() {
+        // Reference: move one cell at a time
+        fn walk(g: &WrappingCoords2d, start_index: usize, dx: i32, dy: i32) -> usize {
+            let (w, h) = (g.width(), g.height());
+            let (mut x, mut y) = g.coords(start_index);
+            for _ in 0..dx.abs() {
+                x += dx.signum();
+                if !(0..w).contains(&x) {
+                    x = WrappingCoords2d::modulo(x, w);
+                    if g.topology() == Topology::Helical {
+                        y = WrappingCoords2d::modulo(y + dx.signum(), h);
+                    }
+                }
+            }
+            for _ in 0..dy.abs() {
+                y += dy.signum();
+                if !(0..h).contains(&y) {
+                    y = WrappingCoords2d::modulo(y, h);
+                    if let Topology::ShiftedTorus(shift) = g.topology() {
+                        x = (x as i64 + dy.signum() as i64 * shift as i64).rem_euclid(w as i64)
+                            as i32;
+                    }
+                }
+            }
+            (y * w + x) as usize
+        }
+        let topologies = [
+            Topology::Helical,
+            Topology::ShiftedTorus(0),
+            Topology::ShiftedTorus(1),
+            Topology::ShiftedTorus(-3),
+            Topology::ShiftedTorus(7),
+            Topology::ShiftedTorus(i32::MIN),
+        ];
+        for (w, h) in [(10, 10), (1, 1), (2, 3), (5, 4), (1, 6), (7, 1)].iter() {
+            let torus = WrappingCoords2d::new(*w, *h).unwrap();
+            for &topology in topologies.iter() {
+                let g = WrappingCoords2d::new_with_topology(*w, *h, topology).unwrap();
+                for a in 0..g.size() {
+                    for dx in -12..=12 {
+                        for dy in -12..=12 {
+                            let expected = walk(&g, a, dx, dy);
+                            assert_eq!(g.shift(a, dx, dy), expected);
+                            assert_eq!(g.shift_oriented(a, dx, dy), (expected, Flip::default()));
+                            if topology == Topology::Helical {
+                                let linear = a as i32 + dx + dy * w;
+                                assert_eq!(expected, linear.rem_euclid(g.size32()) as usize);
+                            }
+                            if a == 0 {
+                                assert_eq!(g.index(dx, dy), expected);
+                            }
+                        }
+                    }
+                    if topology == Topology::ShiftedTorus(0) {
+                        assert_eq!(g.neighbors24(a), torus.neighbors24(a));
+                    }
+                    assert_eq!(g.neighbors16(a), g.neighbors16_array(a).to_vec());
+                    // The displacement is the shortest move that reaches the cell
+                    // No shortest move is longer than the direct move, whose length is less than w + h
+                    let mut shortest = vec![i64::MAX; g.size()];
+                    for dx in -(w + h)..=w + h {
+                        for dy in -(w + h)..=w + h {
+                            let b = g.shift(a, dx, dy);
+                            shortest[b] = shortest[b].min(squared_length((dx, dy)));
+                        }
+                    }
+                    for (b, &length) in shortest.iter().enumerate() {
+                        let (dx, dy) = g.displacement(a, b);
+                        assert_eq!(g.shift(a, dx, dy), b);
+                        assert_eq!(g.squared_distance(a, b), length);
+                    }
+                }
+                g.for_each8(|this_cell_index, neighbors| {
+                    assert_eq!(neighbors, &g.neighbors8_array(this_cell_index));
+                });
+                let mut edges = std::vec::Vec::new();
+                g.for_each_edge4(|a, b| edges.push((a, b)));
+                let mut expected = std::vec::Vec::new();
+                for a in 0..g.size() {
+                    for b in g.neighbors4(a) {
+                        if a != b {
+                            expected.push((a.min(b), a.max(b)));
+                        }
+                    }
+                }
+                expected.sort_unstable();
+                expected.dedup();
+                edges.sort_unstable();
+                assert_eq!(edges, expected);
+            }
+        }
+        // A helical grid of width 1 is a ring of cells along the y axis
+        let g = WrappingCoords2d::new_with_topology(1, 100, Topology::Helical).unwrap();
+        assert_eq!(g.displacement(0, 50), (25, 25));
+        assert_eq!(
+            g.shift(0, i32::MAX, i32::MAX),
+            ((i32::MAX as i64 * 2) % 100) as usize
+        );
+    }
+
+    #[test]
+    fn extreme_coordinates() {
+        let mut grids = test_grids();
+        grids.push(WrappingCoords2d::new(i32::MAX, 1).unwrap());
+        grids.push(WrappingCoords2d::new(1, i32::MAX).unwrap());
+        let values = [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX];
+        for g in grids {
+            // Reference implementation in i64, which cannot overflow for i32 inputs
+            let reference = |x: i64, y: i64| {
+                (y.rem_euclid(g.height() as i64) * g.width() as i64
+                    + x.rem_euclid(g.width() as i64)) as usize
+            };
+            let starts = edge_indices(&g);
+            for &x in values.iter() {
+                for &y in values.iter() {
+                    assert_eq!(g.index(x, y), reference(x as i64, y as i64));
+                    assert_eq!(g.index64(x as i64, y as i64), reference(x as i64, y as i64));
+                    for &start in starts.iter() {
+                        let (sx, sy) = g.coords(start);
+                        let expected = reference(sx as i64 + x as i64, sy as i64 + y as i64);
+                        assert_eq!(g.shift(start, x, y), expected);
+                        assert_eq!(g.shift64(start, x as i64, y as i64), expected);
+                    }
+                    let (x1, y1) = (x as i64, y as i64);
+                    assert_eq!(
+                        g.neighbors4xy(x, y),
+                        vec![
+                            reference(x1 + 1, y1),
+                            reference(x1, y1 + 1),
+                            reference(x1 - 1, y1),
+                            reference(x1, y1 - 1)
+                        ]
+                    );
+                    assert_eq!(
+                        g.neighbors8xy(x, y),
+                        vec![
+                            reference(x1 + 1, y1),
+                            reference(x1 + 1, y1 + 1),
+                            reference(x1, y1 + 1),
+                            reference(x1 - 1, y1 + 1),
+                            reference(x1 - 1, y1),
+                            reference(x1 - 1, y1 - 1),
+                            reference(x1, y1 - 1),
+                            reference(x1 + 1, y1 - 1)
+                        ]
+                    );
+                    let neighbors16 = g.neighbors16xy(x, y);
+                    assert_eq!(neighbors16[2], reference(x1 + 2, y1 + 2));
+                    assert_eq!(neighbors16[10], reference(x1 - 2, y1 - 2));
+                    assert_eq!(g.neighbors24xy(x, y)[8..], neighbors16[..]);
+                }
+            }
+            for &x in [i64::MIN, i64::MAX].iter() {
+                assert_eq!(g.index64(x, x), reference(x, x));
+                assert_eq!(g.shift64(0, x, x), reference(x, x));
+            }
+        }
+    }
+
+    #[test]
+    fn neighbor_orders() {
+        // The default order reproduces the counter-clockwise tables
+        let default = NeighborOrder::default();
+        for (offsets, ring) in [
+            (
+                OFFSETS24.to_vec(),
+                chebyshev_length as fn((i32, i32)) -> i64,
+            ),
+            (moore_offsets(4), chebyshev_length),
+            (von_neumann_offsets(4), manhattan_length),
+            (ring_offsets(0.0, 4.5, (i32::MAX, i32::MAX)), squared_length),
+        ]
+        .iter()
+        {
+            let mut sorted = offsets.clone();
+            default.sort_rings(&mut sorted, *ring);
+            assert_eq!(&sorted, offsets);
+        }
+        assert_eq!(
+            OrderedOffsets::new(default),
+            OrderedOffsets::new(NeighborOrder::default())
+        );
+        assert!(WrappingCoords2d::new(7, 6).unwrap().order().is_default());
+        for &start in Direction::ALL.iter() {
+            for &rotation in [Rotation::CounterClockwise, Rotation::Clockwise].iter() {
+                for &y_axis in [YAxis::Up, YAxis::Down].iter() {
+                    let order = NeighborOrder {
+                        start,
+                        rotation,
+                        y_axis,
+                    };
+                    // Converts an offset on the screen into an offset in grid coordinates
+                    let on_grid = |(dx, dy): (i32, i32)| match y_axis {
+                        YAxis::Up => (dx, dy),
+                        YAxis::Down => (dx, -dy),
+                    };
+                    let g = WrappingCoords2d::new_with_order(7, 6, order).unwrap();
+                    let torus = WrappingCoords2d::new(7, 6).unwrap();
+                    assert_eq!(g.order(), order);
+                    // Each neighbor of the 8-neighborhood is one turn away from the previous one
+                    let mut heading = start;
+                    for (slot, &offset) in g.offsets.n8.iter().enumerate() {
+                        assert_eq!(offset, on_grid(heading.offset()));
+                        assert_eq!(g.slot(heading), slot);
+                        heading = match rotation {
+                            Rotation::CounterClockwise => heading.turn_left(),
+                            Rotation::Clockwise => heading.turn_right(),
+                        };
+                    }
+                    for &d in Direction4::ALL.iter() {
+                        assert_eq!(g.offsets.n4[g.slot4(d)], on_grid(d.offset()));
+                        assert_eq!(g.neighbors8_array(8)[g.slot(d)], g.step(8, d));
+                    }
+                    for idx in 0..g.size() {
+                        let mut sorted = g.neighbors24(idx);
+                        let mut expected = torus.neighbors24(idx);
+                        sorted.sort_unstable();
+                        expected.sort_unstable();
+                        assert_eq!(sorted, expected);
+                        let (dx, dy) = on_grid(start.offset());
+                        assert_eq!(g.neighbors8(idx)[0], g.shift(idx, dx, dy));
+                        assert_eq!(g.neighbors16(idx)[0], g.shift(idx, 2 * dx, 2 * dy));
+                        assert_eq!(g.neighbors4_array(idx).to_vec(), g.neighbors4(idx));
+                        assert_eq!(g.neighbors24_array(idx)[..8], g.neighbors8_array(idx));
+                        assert_eq!(g.neighbors_moore(idx, 2), g.neighbors24(idx));
+                        assert_eq!(g.neighbors_von_neumann(idx, 1), g.neighbors4(idx));
+                        assert_eq!(g.neighbors_disk(idx, 1.0), g.neighbors4(idx));
+                        let collect = |neighborhood| {
+                            g.neighbors_iter(idx, neighborhood)
+                                .collect::<std::vec::Vec<_>>()
+                        };
+                        assert_eq!(collect(Neighborhood::N8), g.neighbors8(idx));
+                        assert_eq!(collect(Neighborhood::N16), g.neighbors16(idx));
+                    }
+                    g.for_each4(|idx, neighbors| assert_eq!(*neighbors, g.neighbors4_array(idx)));
+                    g.for_each8(|idx, neighbors| assert_eq!(*neighbors, g.neighbors8_array(idx)));
+                    g.for_each16(|idx, neighbors| assert_eq!(*neighbors, g.neighbors16_array(idx)));
+                    g.for_each24(|idx, neighbors| assert_eq!(*neighbors, g.neighbors24_array(idx)));
+                    g.for_each_moore(3, |idx, neighbors| {
+                        assert_eq!(*neighbors, g.neighbors_moore(idx, 3)[..])
+                    });
+                    g.for_each_disk(2.5, |idx, neighbors| {
+                        assert_eq!(*neighbors, g.neighbors_disk(idx, 2.5)[..])
+                    });
+                    let mut pairs = std::vec::Vec::new();
+                    g.for_each_pair8(|idx, neighbor| pairs.push((idx, neighbor)));
+                    assert_eq!(
+                        pairs[..8],
+                        g.neighbors8(0)
+                            .iter()
+                            .map(|&n| (0, n))
+                            .collect::<std::vec::Vec<_>>()[..]
+                    );
+                    let l2d =
+                        LinkedCoords2d::new(WrappingCoords2d::new_with_order(7, 6, order).unwrap());
+                    assert_eq!(l2d.neighbors8(20), g.neighbors8(20));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        assert!(matches!(
+            WrappingCoords2d::from_bounds(1, 0, 0, 0),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingCoords2d::from_bounds(0, 0, 5, -5),
+            Err(ErrorKind::DimensionsLessThan1)
+        ));
+        assert!(matches!(
+            WrappingCoords2d::from_bounds(i32::MIN, i32::MAX, 0, 0),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(matches!(
+            WrappingCoords2d::from_bounds(-100000, 100000, -100000, 100000),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert_eq!(
+            WrappingCoords2d::from_bounds(0, 9, 0, 9).unwrap(),
+            WrappingCoords2d::new(10, 10).unwrap()
+        );
+        for &(min_x, max_x, min_y, max_y) in
+            [(-16, 16, -16, 16), (-3, 0, 2, 7), (5, 5, -1, 0)].iter()
+        {
+            let g = WrappingCoords2d::from_bounds(min_x, max_x, min_y, max_y).unwrap();
+            let plain = WrappingCoords2d::new(max_x - min_x + 1, max_y - min_y + 1).unwrap();
+            assert_eq!(
+                (g.min_x(), g.max_x(), g.min_y(), g.max_y()),
+                (min_x, max_x, min_y, max_y)
+            );
+            assert_eq!(g.index(min_x, min_y), 0);
+            assert_eq!(g.coords(g.size() - 1), (max_x, max_y));
+            for idx in 0..g.size() {
+                let (x, y) = g.coords(idx);
+                assert!((min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y));
+                assert_eq!(g.coords_checked(idx), Some((x, y)));
+                assert_eq!(plain.coords(idx), (x - min_x, y - min_y));
+                assert_eq!(g.index(x, y), idx);
+                assert_eq!(g.index_checked(x, y), Some(idx));
+                assert_eq!(g.index64(x.into(), y.into()), idx);
+                assert_eq!(g.shift(idx, 3, -2), plain.shift(idx, 3, -2));
+                assert_eq!(g.neighbors24(idx), plain.neighbors24(idx));
+                assert_eq!(
+                    g.neighbors8xy(x, y),
+                    plain.neighbors8xy(x - min_x, y - min_y)
+                );
+                assert_eq!(g.displacement(idx, 0), plain.displacement(idx, 0));
+            }
+            for &(x, y) in [(i32::MIN, i32::MAX), (-17, 40), (0, 0)].iter() {
+                let expected = plain.index64(x as i64 - min_x as i64, y as i64 - min_y as i64);
+                assert_eq!(g.index(x, y), expected);
+                assert_eq!(g.index64(x.into(), y.into()), expected);
+            }
+            // Far coordinates are reduced exactly
+            let (w, h) = (plain.width() as i128, plain.height() as i128);
+            let mx = (i64::MIN as i128 - min_x as i128).rem_euclid(w);
+            let my = (i64::MAX as i128 - min_y as i128).rem_euclid(h);
+            assert_eq!(g.index64(i64::MIN, i64::MAX), (my * w + mx) as usize);
+        }
+        // Bounds compose with boundaries, topologies and neighbor orders
+        let order = NeighborOrder {
+            start: Direction::North,
+            rotation: Rotation::Clockwise,
+            y_axis: YAxis::Down,
+        };
+        let make = |kind| match kind {
+            0 => WrappingCoords2d::new_with_boundaries(7, 5, Boundary::Wrap, Boundary::Absorb),
+            1 => WrappingCoords2d::new_with_boundaries(7, 5, Boundary::Reflect, Boundary::Clamp),
+            2 => WrappingCoords2d::new_with_topology(7, 5, Topology::KleinBottle),
+            _ => WrappingCoords2d::new_with_order(7, 5, order),
+        };
+        for kind in 0..4 {
+            let plain = make(kind).unwrap();
+            let (min_x, min_y) = (-3, 10);
+            let g = make(kind).unwrap().with_min_coords(min_x, min_y).unwrap();
+            assert_eq!(g.with_min_coords(0, 0).unwrap(), plain);
+            let g = make(kind).unwrap().with_min_coords(min_x, min_y).unwrap();
+            for idx in 0..g.size() {
+                let (x, y) = g.coords(idx);
+                assert_eq!(plain.coords(idx), (x - min_x, y - min_y));
+                assert_eq!(g.neighbors8(idx), plain.neighbors8(idx));
+                for &(dx, dy) in [(0, 0), (9, -2), (-20, 13)].iter() {
+                    assert_eq!(
+                        g.index_checked(x + dx, y + dy),
+                        plain.index_checked(x - min_x + dx, y - min_y + dy)
+                    );
+                }
+            }
+        }
+        assert!(matches!(
+            WrappingCoords2d::new(10, 10)
+                .unwrap()
+                .with_min_coords(0, i32::MAX - 8),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        assert!(WrappingCoords2d::new(10, 10)
+            .unwrap()
+            .with_min_coords(0, i32::MAX - 9)
+            .is_ok());
+        // The widest grid starts at i32::MIN
+        let g = WrappingCoords2d::from_bounds(i32::MIN, -2, 0, 0).unwrap();
+        assert_eq!(g.width(), i32::MAX);
+        assert_eq!(g.index(i32::MIN, 0), 0);
+        assert_eq!(g.coords(g.size() - 1), (-2, 0));
+        assert_eq!(g.index(i32::MAX, 0), 1);
+        assert_eq!(g.index(-1, 0), 0);
+    }
+
+    #[test]
+    fn invalid_start_indices() {
+        for g in [
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(7, 3).unwrap(),
+            WrappingCoords2d::new_with_topology(10, 10, Topology::KleinBottle).unwrap(),
+            WrappingCoords2d::new_with_boundaries(10, 10, Boundary::Wrap, Boundary::Absorb)
+                .unwrap(),
+        ]
+        .iter()
+        {
+            for &start in [g.size(), 2 * g.size() + 50, usize::MAX].iter() {
+                let valid = start % g.size();
+                assert_eq!(g.shift_checked(start, 0, 0), Some(valid));
+                assert_eq!(g.shift_checked(start, 1, 0), g.shift_checked(valid, 1, 0));
+                assert_eq!(g.shift64(start, -1, 0), g.shift64(valid, -1, 0));
+                assert_eq!(g.neighbors8(start), g.neighbors8(valid));
+                assert_eq!(g.neighbors16(start), g.neighbors16(valid));
+                assert_eq!(
+                    g.neighbors_iter(start, Neighborhood::N24)
+                        .collect::<std::vec::Vec<_>>(),
+                    g.neighbors24(valid)
+                );
+                assert!(g.neighbors24(start).iter().all(|&index| index < g.size()));
+            }
+        }
+        let w2d = WrappingCoords2d::new(10, 10).unwrap();
+        assert_eq!(w2d.shift(250, 0, 0), 50);
+        assert_eq!(w2d.neighbors4_array(250), w2d.neighbors4_array(50));
+    }
 }
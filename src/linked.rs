@@ -0,0 +1,481 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Represents a 2D grid with extra links between cells, such as tunnels, bridges, or teleporters.
+///
+/// A link from one cell to another makes the second cell a neighbor of the first one, in addition to the
+/// neighbors given by the grid. Links can be directed or undirected. The neighbor functions of a `LinkedCoords2d`
+/// return the neighbors given by the grid first, followed by the linked cells in the order the links were added.
+/// The distance and path functions treat links as edges of length 1.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+/// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+/// // A tunnel joins (2, 2) and (7, 7):
+/// l2d.add_undirected_link(22, 77).unwrap();
+/// assert_eq!(l2d.neighbors4(22), vec![23, 32, 21, 12, 77]);
+/// assert_eq!(l2d.distance4(21, 78), Some(3));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct LinkedCoords2d {
+    /// Underlying grid.
+    w2d: WrappingCoords2d,
+    /// Linked cells, by the index of the cell where each link starts.
+    links: std::collections::HashMap<usize, std::vec::Vec<usize>>,
+}
+
+impl LinkedCoords2d {
+    /// Constructs a new LinkedCoords2d object on the grid `w2d`, without links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// assert!(l2d.links(0).is_empty());
+    /// ```
+    pub fn new(w2d: WrappingCoords2d) -> LinkedCoords2d {
+        LinkedCoords2d {
+            w2d,
+            links: std::collections::HashMap::new(),
+        }
+    }
+    /// Returns the underlying grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// assert_eq!(l2d.grid().size(), 100);
+    /// ```
+    pub fn grid(&self) -> &WrappingCoords2d {
+        &self.w2d
+    }
+    /// Adds a directed link that makes the cell given by `to_index` a neighbor of the cell given by `from_index`.
+    /// Adding a link twice has no effect.
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::IndexOutOfRange` if `from_index` or `to_index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// // A one-way teleporter:
+    /// l2d.add_link(0, 55).unwrap();
+    /// assert_eq!(l2d.links(0), &[55]);
+    /// assert!(l2d.links(55).is_empty());
+    /// assert!(matches!(l2d.add_link(0, 100), Err(ErrorKind::IndexOutOfRange)));
+    /// ```
+    pub fn add_link(&mut self, from_index: usize, to_index: usize) -> Result<(), ErrorKind> {
+        if from_index >= self.w2d.size() || to_index >= self.w2d.size() {
+            return Err(ErrorKind::IndexOutOfRange);
+        }
+        let links = self.links.entry(from_index).or_default();
+        if !links.contains(&to_index) {
+            links.push(to_index);
+        }
+        Ok(())
+    }
+    /// Adds two directed links, one in each direction, between the cells given by `index_a` and `index_b`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::IndexOutOfRange` if `index_a` or `index_b` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_undirected_link(0, 55).unwrap();
+    /// assert_eq!(l2d.links(0), &[55]);
+    /// assert_eq!(l2d.links(55), &[0]);
+    /// ```
+    pub fn add_undirected_link(&mut self, index_a: usize, index_b: usize) -> Result<(), ErrorKind> {
+        self.add_link(index_a, index_b)?;
+        self.add_link(index_b, index_a)
+    }
+    /// Returns the cells linked from the cell given by `index`, in the order the links were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(0, 55).unwrap();
+    /// l2d.add_link(0, 33).unwrap();
+    /// assert_eq!(l2d.links(0), &[55, 33]);
+    /// ```
+    pub fn links(&self, index: usize) -> &[usize] {
+        self.links.get(&index).map_or(&[], |links| &links[..])
+    }
+    /// Returns the neighbors of the cell given by `start_index` through `offsets`, followed by its linked cells.
+    fn neighbors_offsets(
+        &self,
+        start_index: usize,
+        offsets: &[(i32, i32)],
+    ) -> std::vec::Vec<usize> {
+        let mut neighbors = self.w2d.neighbors_offsets(start_index, offsets);
+        neighbors.extend_from_slice(self.links(start_index));
+        neighbors
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 4 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors4`], followed by its linked cells.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(95, 0).unwrap();
+    /// assert_eq!(l2d.neighbors4(95), vec![96, 5, 94, 85, 0]);
+    /// ```
+    pub fn neighbors4(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n4)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 8 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors8`], followed by its linked cells.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(95, 0).unwrap();
+    /// assert_eq!(l2d.neighbors8(95), vec![96, 6, 5, 4, 94, 84, 85, 86, 0]);
+    /// ```
+    pub fn neighbors8(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n8)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 16 second
+    /// neighbors, in the order of [`WrappingCoords2d::neighbors16`], followed by its linked cells.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(95, 0).unwrap();
+    /// assert_eq!(l2d.neighbors16(95)[16], 0);
+    /// ```
+    pub fn neighbors16(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n16)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its 24 nearest
+    /// neighbors, in the order of [`WrappingCoords2d::neighbors24`], followed by its linked cells.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(95, 0).unwrap();
+    /// assert_eq!(l2d.neighbors24(95)[24], 0);
+    /// ```
+    pub fn neighbors24(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n24)
+    }
+    /// Calls `f` on each pair of a cell and one of its neighbors through `offsets`, or one of its linked cells.
+    fn for_each_pair_offsets<F>(&self, offsets: &[(i32, i32)], mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.w2d
+            .for_each_offsets(offsets, |this_cell_index, neighbors| {
+                for &neighbor_index in neighbors.iter().chain(self.links(this_cell_index)) {
+                    f(this_cell_index, neighbor_index);
+                }
+            });
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 4 neighbors,
+    /// or one of its linked cells, in the order of [`neighbors4`](#method.neighbors4).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_undirected_link(0, 55).unwrap();
+    /// let mut calls_counter = 0;
+    /// l2d.for_each_pair4(|this_cell_index, neighbor_index| {
+    ///     assert!(l2d.neighbors4(this_cell_index).contains(&neighbor_index));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 4 * 100 + 2);
+    /// ```
+    pub fn for_each_pair4<F>(&self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each_pair_offsets(&self.w2d.offsets.n4, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 8 neighbors,
+    /// or one of its linked cells, in the order of [`neighbors8`](#method.neighbors8).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(0, 55).unwrap();
+    /// let mut calls_counter = 0;
+    /// l2d.for_each_pair8(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 8 * 100 + 1);
+    /// ```
+    pub fn for_each_pair8<F>(&self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each_pair_offsets(&self.w2d.offsets.n8, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 16 second neighbors,
+    /// or one of its linked cells, in the order of [`neighbors16`](#method.neighbors16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(0, 55).unwrap();
+    /// let mut calls_counter = 0;
+    /// l2d.for_each_pair16(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 16 * 100 + 1);
+    /// ```
+    pub fn for_each_pair16<F>(&self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each_pair_offsets(&self.w2d.offsets.n16, f)
+    }
+    /// Calls a closure `f` on each cell of the grid. Each call acts on the cell and one of its 24 nearest neighbors,
+    /// or one of its linked cells, in the order of [`neighbors24`](#method.neighbors24).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(0, 55).unwrap();
+    /// let mut calls_counter = 0;
+    /// l2d.for_each_pair24(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 24 * 100 + 1);
+    /// ```
+    pub fn for_each_pair24<F>(&self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each_pair_offsets(&self.w2d.offsets.n24, f)
+    }
+    /// Returns a shortest path from `from_index` to `to_index` through the neighbors given by `offsets` and the links,
+    /// using a breadth-first search.
+    fn path_offsets(
+        &self,
+        from_index: usize,
+        to_index: usize,
+        offsets: &[(i32, i32)],
+    ) -> Option<std::vec::Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+        let mut previous = vec![UNVISITED; self.w2d.size()];
+        let mut queue = std::collections::VecDeque::new();
+        previous[from_index] = from_index;
+        queue.push_back(from_index);
+        while let Some(index) = queue.pop_front() {
+            if index == to_index {
+                let mut path = vec![to_index];
+                let mut index = to_index;
+                while index != from_index {
+                    index = previous[index];
+                    path.push(index);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbor_index in self.neighbors_offsets(index, offsets) {
+                if previous[neighbor_index] == UNVISITED {
+                    previous[neighbor_index] = index;
+                    queue.push_back(neighbor_index);
+                }
+            }
+        }
+        None
+    }
+    /// Returns a shortest path from the cell given by `from_index` to the cell given by `to_index`, through the
+    /// 4-neighborhood and the links, or `None` if there is no such path. The path starts with `from_index`
+    /// and ends with `to_index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from_index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(21, 76).unwrap();
+    /// assert_eq!(l2d.path4(20, 77), Some(vec![20, 21, 76, 77]));
+    /// ```
+    pub fn path4(&self, from_index: usize, to_index: usize) -> Option<std::vec::Vec<usize>> {
+        self.path_offsets(from_index, to_index, &self.w2d.offsets.n4)
+    }
+    /// Returns a shortest path from the cell given by `from_index` to the cell given by `to_index`, through the
+    /// 8-neighborhood and the links, or `None` if there is no such path. The path starts with `from_index`
+    /// and ends with `to_index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from_index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// l2d.add_link(22, 77).unwrap();
+    /// assert_eq!(l2d.path8(11, 88), Some(vec![11, 22, 77, 88]));
+    /// ```
+    pub fn path8(&self, from_index: usize, to_index: usize) -> Option<std::vec::Vec<usize>> {
+        self.path_offsets(from_index, to_index, &self.w2d.offsets.n8)
+    }
+    /// Returns the number of steps through the 4-neighborhood and the links along a shortest path from the cell
+    /// given by `from_index` to the cell given by `to_index`, or `None` if there is no such path.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from_index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// assert_eq!(l2d.distance4(0, 55), Some(10));
+    /// l2d.add_link(0, 55).unwrap();
+    /// assert_eq!(l2d.distance4(0, 55), Some(1));
+    /// // The link is directed:
+    /// assert_eq!(l2d.distance4(55, 0), Some(10));
+    /// ```
+    pub fn distance4(&self, from_index: usize, to_index: usize) -> Option<usize> {
+        self.path4(from_index, to_index).map(|path| path.len() - 1)
+    }
+    /// Returns the number of steps through the 8-neighborhood and the links along a shortest path from the cell
+    /// given by `from_index` to the cell given by `to_index`, or `None` if there is no such path.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from_index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{LinkedCoords2d, WrappingCoords2d};
+    /// let mut l2d = LinkedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap());
+    /// assert_eq!(l2d.distance8(0, 55), Some(5));
+    /// l2d.add_link(11, 44).unwrap();
+    /// assert_eq!(l2d.distance8(0, 55), Some(3));
+    /// ```
+    pub fn distance8(&self, from_index: usize, to_index: usize) -> Option<usize> {
+        self.path8(from_index, to_index).map(|path| path.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Boundary;
+
+    #[test]
+    fn links() {
+        let torus = WrappingCoords2d::new(10, 10).unwrap();
+        let mut l2d = LinkedCoords2d::new(torus.clone());
+        // Without links, distances are the grid distances
+        for a in 0..torus.size() {
+            for b in [0, 9, 45, 99].iter() {
+                assert_eq!(
+                    l2d.distance4(a, *b),
+                    Some(torus.manhattan_distance(a, *b) as usize)
+                );
+                assert_eq!(
+                    l2d.distance8(a, *b),
+                    Some(torus.chebyshev_distance(a, *b) as usize)
+                );
+            }
+        }
+        l2d.add_undirected_link(0, 55).unwrap();
+        l2d.add_link(0, 55).unwrap();
+        l2d.add_link(33, 33).unwrap();
+        assert_eq!(l2d.links(0), &[55]);
+        assert_eq!(l2d.links(33), &[33]);
+        assert!(matches!(
+            l2d.add_link(100, 0),
+            Err(ErrorKind::IndexOutOfRange)
+        ));
+        let mut pairs = std::vec::Vec::new();
+        l2d.for_each_pair8(|a, b| pairs.push((a, b)));
+        let expected: std::vec::Vec<(usize, usize)> = (0..torus.size())
+            .flat_map(|a| l2d.neighbors8(a).into_iter().map(move |b| (a, b)))
+            .collect();
+        assert_eq!(pairs, expected);
+        assert_eq!(pairs.len(), 8 * 100 + 3);
+        // Paths are shortest and follow neighbors or links
+        for a in 0..torus.size() {
+            let path = l2d.path4(a, 56).unwrap();
+            assert_eq!(path.len() - 1, l2d.distance4(a, 56).unwrap());
+            assert_eq!((path[0], path[path.len() - 1]), (a, 56));
+            for step in path.windows(2) {
+                assert!(l2d.neighbors4(step[0]).contains(&step[1]));
+            }
+            let through_link =
+                torus.manhattan_distance(a, 0) + 1 + torus.manhattan_distance(55, 56);
+            let direct = torus.manhattan_distance(a, 56);
+            assert_eq!(path.len() - 1, direct.min(through_link) as usize);
+        }
+        // Links cross absorbing boundaries
+        let w2d = WrappingCoords2d::new_with_boundaries(5, 1, Boundary::Absorb, Boundary::Absorb)
+            .unwrap();
+        let mut l2d = LinkedCoords2d::new(w2d);
+        assert_eq!(l2d.distance4(0, 4), Some(4));
+        assert_eq!(l2d.distance4(4, 0), Some(4));
+        l2d.add_link(4, 0).unwrap();
+        assert_eq!(l2d.path4(4, 0), Some(vec![4, 0]));
+        assert_eq!(l2d.distance4(0, 4), Some(4));
+    }
+}
@@ -0,0 +1,523 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, Stencil, WrappingCoords2d};
+
+/// Marks the compact index of an inactive cell.
+const INACTIVE: usize = usize::MAX;
+
+/// Represents a 2D grid with inactive cells, such as water, walls, or cells outside of the study area.
+///
+/// The neighbor functions of a `MaskedCoords2d` skip inactive neighbors, and its `for_each` functions skip
+/// inactive cells. Each active cell also has a compact index in the range [0, active_count), so that a
+/// `std::vec::Vec` of length [`active_count`](#method.active_count) stores data for the active cells only.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// // A wall runs along the column x = 5:
+/// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+/// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+/// assert_eq!(m2d.active_count(), 90);
+/// // The cell at (4, 4) has no right neighbor:
+/// assert_eq!(m2d.neighbors4(44), vec![54, 43, 34]);
+/// // Dense storage of the active cells only:
+/// let mut heights = vec![0.0; m2d.active_count()];
+/// heights[m2d.to_compact(44).unwrap()] = 1.5;
+/// assert_eq!(m2d.to_full(m2d.to_compact(44).unwrap()), Some(44));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct MaskedCoords2d {
+    /// Underlying grid.
+    w2d: WrappingCoords2d,
+    /// `true` for the active cells, by full index.
+    active: std::vec::Vec<bool>,
+    /// Compact index of each cell, by full index; `INACTIVE` for inactive cells.
+    compact: std::vec::Vec<usize>,
+    /// Full index of each active cell, by compact index, in increasing order.
+    full: std::vec::Vec<usize>,
+}
+
+impl MaskedCoords2d {
+    /// Constructs a new MaskedCoords2d object on the grid `w2d`. `mask[index]` is `true` for the active cells.
+    ///
+    /// # Errors
+    ///
+    /// This function returns `ErrorKind::MaskSizeMismatch` if the length of `mask` differs from `w2d.size()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(2, 2).unwrap();
+    /// assert!(MaskedCoords2d::new(w2d, &[true, false, true, true]).is_ok());
+    /// let w2d = WrappingCoords2d::new(2, 2).unwrap();
+    /// assert!(matches!(MaskedCoords2d::new(w2d, &[true]), Err(ErrorKind::MaskSizeMismatch)));
+    /// ```
+    pub fn new(w2d: WrappingCoords2d, mask: &[bool]) -> Result<MaskedCoords2d, ErrorKind> {
+        if mask.len() != w2d.size() {
+            return Err(ErrorKind::MaskSizeMismatch);
+        }
+        let mut compact = vec![INACTIVE; mask.len()];
+        let mut full = std::vec::Vec::new();
+        for (index, _) in mask.iter().enumerate().filter(|(_, &active)| active) {
+            compact[index] = full.len();
+            full.push(index);
+        }
+        Ok(MaskedCoords2d {
+            w2d,
+            active: mask.to_vec(),
+            compact,
+            full,
+        })
+    }
+    /// Returns the underlying grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap(), &[true; 100]).unwrap();
+    /// assert_eq!(m2d.grid().size(), 100);
+    /// ```
+    pub fn grid(&self) -> &WrappingCoords2d {
+        &self.w2d
+    }
+    /// Returns `true` if the cell given by `index` is active.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(2, 1).unwrap(), &[false, true]).unwrap();
+    /// assert!(!m2d.is_active(0));
+    /// assert!(m2d.is_active(1));
+    /// ```
+    pub fn is_active(&self, index: usize) -> bool {
+        self.active[index]
+    }
+    /// Returns the number of active cells. Use this to initialize dense containers indexed by compact indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(2, 2).unwrap(), &[true, false, true, true]).unwrap();
+    /// assert_eq!(m2d.active_count(), 3);
+    /// ```
+    pub fn active_count(&self) -> usize {
+        self.full.len()
+    }
+    /// Returns the full indices of the active cells, in increasing order. The position of each full index
+    /// in the slice is its compact index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(2, 2).unwrap(), &[true, false, true, true]).unwrap();
+    /// assert_eq!(m2d.active_indices(), &[0, 2, 3]);
+    /// ```
+    pub fn active_indices(&self) -> &[usize] {
+        &self.full
+    }
+    /// Returns the compact index of the cell given by the full index `index`,
+    /// or `None` if the cell is inactive or `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(2, 2).unwrap(), &[true, false, true, true]).unwrap();
+    /// assert_eq!(m2d.to_compact(2), Some(1));
+    /// assert_eq!(m2d.to_compact(1), None);
+    /// assert_eq!(m2d.to_compact(4), None);
+    /// ```
+    pub fn to_compact(&self, index: usize) -> Option<usize> {
+        match self.compact.get(index) {
+            Some(&compact_index) if compact_index != INACTIVE => Some(compact_index),
+            _ => None,
+        }
+    }
+    /// Returns the full index of the cell given by the compact index `compact_index`,
+    /// or `None` if `compact_index` is not in the range [0, active_count).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(2, 2).unwrap(), &[true, false, true, true]).unwrap();
+    /// assert_eq!(m2d.to_full(1), Some(2));
+    /// assert_eq!(m2d.to_full(3), None);
+    /// ```
+    pub fn to_full(&self, compact_index: usize) -> Option<usize> {
+        self.full.get(compact_index).copied()
+    }
+    /// Returns the active neighbors of the cell given by `start_index`, in the order of `offsets`.
+    fn neighbors_offsets(
+        &self,
+        start_index: usize,
+        offsets: &[(i32, i32)],
+    ) -> std::vec::Vec<usize> {
+        let mut neighbors = self.w2d.neighbors_offsets(start_index, offsets);
+        neighbors.retain(|&index| self.active[index]);
+        neighbors
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its active
+    /// 4 neighbors, in the order of [`WrappingCoords2d::neighbors4`]. The cell itself does not need to be active.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let mut mask = vec![true; 100];
+    /// mask[96] = false;
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap(), &mask).unwrap();
+    /// assert_eq!(m2d.neighbors4(95), vec![5, 94, 85]);
+    /// ```
+    pub fn neighbors4(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n4)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its active
+    /// 8 neighbors, in the order of [`WrappingCoords2d::neighbors8`]. The cell itself does not need to be active.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let mut mask = vec![true; 100];
+    /// mask[96] = false;
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap(), &mask).unwrap();
+    /// assert_eq!(m2d.neighbors8(95), vec![6, 5, 4, 94, 84, 85, 86]);
+    /// ```
+    pub fn neighbors8(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n8)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its active
+    /// 16 second neighbors, in the order of [`WrappingCoords2d::neighbors16`]. The cell itself does not need to be active.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let mut mask = vec![true; 100];
+    /// mask[97] = false;
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap(), &mask).unwrap();
+    /// assert_eq!(m2d.neighbors16(95).len(), 15);
+    /// ```
+    pub fn neighbors16(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n16)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its active
+    /// 24 nearest neighbors, in the order of [`WrappingCoords2d::neighbors24`]. The cell itself does not need to be active.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let mut mask = vec![true; 100];
+    /// mask[96] = false;
+    /// mask[97] = false;
+    /// let m2d = MaskedCoords2d::new(WrappingCoords2d::new(10, 10).unwrap(), &mask).unwrap();
+    /// assert_eq!(m2d.neighbors24(95).len(), 22);
+    /// ```
+    pub fn neighbors24(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &self.w2d.offsets.n24)
+    }
+    /// This function takes the cell given by `start_index` and returns a vector of the indices to its active
+    /// neighbors given by `stencil`, in the order of the stencil offsets. The cell itself does not need to be active.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let wind = w2d.stencil(&[(1, 0), (2, 0)]);
+    /// let mut mask = vec![true; 100];
+    /// mask[56] = false;
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// assert_eq!(m2d.neighbors_stencil(55, &wind), vec![57]);
+    /// ```
+    pub fn neighbors_stencil(&self, start_index: usize, stencil: &Stencil) -> std::vec::Vec<usize> {
+        self.neighbors_offsets(start_index, &stencil.offsets)
+    }
+    /// Wraps `f` into a closure for the `for_each` functions of the grid that skips inactive cells and neighbors.
+    fn active_only<'a, F>(&'a self, mut f: F) -> impl FnMut(usize, &[usize]) + 'a
+    where
+        F: FnMut(usize, &[usize]) + 'a,
+    {
+        let mut active_neighbors = std::vec::Vec::new();
+        move |this_cell_index, neighbors| {
+            if self.active[this_cell_index] {
+                active_neighbors.clear();
+                active_neighbors.extend(neighbors.iter().filter(|&&index| self.active[index]));
+                f(this_cell_index, &active_neighbors);
+            }
+        }
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and its active 4 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors4`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// let mut calls_counter = 0;
+    /// m2d.for_each4(|this_cell_index, neighbors| {
+    ///     assert!(m2d.is_active(this_cell_index));
+    ///     assert_eq!(neighbors, &m2d.neighbors4(this_cell_index)[..]);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, m2d.active_count());
+    /// ```
+    pub fn for_each4<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.w2d
+            .for_each_offsets(&self.w2d.offsets.n4, self.active_only(f))
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and its active 8 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// m2d.for_each8(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors, &m2d.neighbors8(this_cell_index)[..]);
+    /// });
+    /// ```
+    pub fn for_each8<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.w2d
+            .for_each_offsets(&self.w2d.offsets.n8, self.active_only(f))
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and its active 16 second
+    /// neighbors, in the order of [`WrappingCoords2d::neighbors16`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// m2d.for_each16(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors, &m2d.neighbors16(this_cell_index)[..]);
+    /// });
+    /// ```
+    pub fn for_each16<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.w2d
+            .for_each_offsets(&self.w2d.offsets.n16, self.active_only(f))
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and its active 24 nearest
+    /// neighbors, in the order of [`WrappingCoords2d::neighbors24`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// m2d.for_each24(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors, &m2d.neighbors24(this_cell_index)[..]);
+    /// });
+    /// ```
+    pub fn for_each24<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.w2d
+            .for_each_offsets(&self.w2d.offsets.n24, self.active_only(f))
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and its active neighbors
+    /// given by `stencil`, in the order of the stencil offsets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let knight = w2d.stencil(&[(2, 1), (1, 2), (-1, 2), (-2, 1), (-2, -1), (-1, -2), (1, -2), (2, -1)]);
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// m2d.for_each_stencil(&knight, |this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors, &m2d.neighbors_stencil(this_cell_index, &knight)[..]);
+    /// });
+    /// ```
+    pub fn for_each_stencil<F>(&self, stencil: &Stencil, f: F)
+    where
+        F: FnMut(usize, &[usize]),
+    {
+        self.w2d.for_each_stencil(stencil, self.active_only(f))
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and one of its active 4 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors4`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// let mut calls_counter = 0;
+    /// m2d.for_each_pair4(|this_cell_index, neighbor_index| {
+    ///     assert!(m2d.is_active(this_cell_index) && m2d.is_active(neighbor_index));
+    ///     calls_counter += 1;
+    /// });
+    /// // Two columns lose one of their 4 neighbors:
+    /// assert_eq!(calls_counter, 4 * 90 - 2 * 10);
+    /// ```
+    pub fn for_each_pair4<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each4(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+    /// Calls a closure `f` on each active cell of the grid. Each call acts on the cell and one of its active 8 neighbors,
+    /// in the order of [`WrappingCoords2d::neighbors8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{MaskedCoords2d, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// let mask: Vec<bool> = (0..w2d.size()).map(|index| w2d.coords(index).0 != 5).collect();
+    /// let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+    /// let mut calls_counter = 0;
+    /// m2d.for_each_pair8(|_, _| calls_counter += 1);
+    /// // Two columns lose 3 of their 8 neighbors:
+    /// assert_eq!(calls_counter, 8 * 90 - 2 * 3 * 10);
+    /// ```
+    pub fn for_each_pair8<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each8(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masked_grids() {
+        let torus = WrappingCoords2d::new(7, 5).unwrap();
+        // A pseudo-random mask
+        let mask: std::vec::Vec<bool> = (0..torus.size()).map(|index| index * 7 % 5 != 2).collect();
+        let m2d = MaskedCoords2d::new(torus.clone(), &mask).unwrap();
+        assert_eq!(
+            m2d.active_count(),
+            mask.iter().filter(|&&active| active).count()
+        );
+        // Compact indices are dense and preserve the order of the full indices
+        for (compact_index, &index) in m2d.active_indices().iter().enumerate() {
+            assert!(m2d.is_active(index));
+            assert_eq!(m2d.to_compact(index), Some(compact_index));
+            assert_eq!(m2d.to_full(compact_index), Some(index));
+        }
+        assert_eq!(m2d.to_full(m2d.active_count()), None);
+        for index in 0..torus.size() {
+            assert_eq!(m2d.to_compact(index).is_some(), mask[index]);
+            let expected: std::vec::Vec<usize> = torus
+                .neighbors24(index)
+                .into_iter()
+                .filter(|&neighbor_index| mask[neighbor_index])
+                .collect();
+            assert_eq!(m2d.neighbors24(index), expected);
+        }
+        let mut visited = std::vec::Vec::new();
+        m2d.for_each24(|this_cell_index, neighbors| {
+            assert_eq!(neighbors, &m2d.neighbors24(this_cell_index)[..]);
+            visited.push(this_cell_index);
+        });
+        assert_eq!(visited, m2d.active_indices());
+        // Masks compose with boundaries
+        let w2d = WrappingCoords2d::new_with_boundaries(
+            7,
+            5,
+            crate::Boundary::Absorb,
+            crate::Boundary::Reflect,
+        )
+        .unwrap();
+        let m2d = MaskedCoords2d::new(w2d, &mask).unwrap();
+        let mut pairs_counter = 0;
+        m2d.for_each_pair8(|this_cell_index, neighbor_index| {
+            assert!(m2d
+                .grid()
+                .neighbors8(this_cell_index)
+                .contains(&neighbor_index));
+            assert!(mask[this_cell_index] && mask[neighbor_index]);
+            pairs_counter += 1;
+        });
+        let expected: usize = m2d
+            .active_indices()
+            .iter()
+            .map(|&index| m2d.neighbors8(index).len())
+            .sum();
+        assert_eq!(pairs_counter, expected);
+    }
+}
@@ -0,0 +1,601 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::{ErrorKind, WrappingCoords2d};
+
+/// Offsets `(dx, dy)` of the 3 edge neighbors of a triangle that points up, ordered counter-clockwise.
+const UP_OFFSETS3: [(i32, i32); 3] = [(1, 0), (-1, 0), (0, -1)];
+
+/// Offsets `(dx, dy)` of the 3 edge neighbors of a triangle that points down, ordered counter-clockwise.
+const DOWN_OFFSETS3: [(i32, i32); 3] = [(0, 1), (-1, 0), (1, 0)];
+
+/// Offsets `(dx, dy)` of the 12 vertex neighbors of a triangle that points up, ordered counter-clockwise.
+const UP_OFFSETS12: [(i32, i32); 12] = [
+    (2, 0),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-2, 0),
+    (-2, -1),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, -1),
+];
+
+/// Offsets `(dx, dy)` of the 12 vertex neighbors of a triangle that points down, ordered counter-clockwise.
+const DOWN_OFFSETS12: [(i32, i32); 12] = [
+    (2, 0),
+    (2, 1),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-2, 1),
+    (-2, 0),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+];
+
+/// Represents a 2D grid of triangles with wrapping.
+///
+/// The triangles are laid out in rows, and each row alternates between triangles that point up and triangles
+/// that point down. Like in [`WrappingCoords2d`], the coordinates `(x, y)` give the column and the row of a triangle,
+/// the index of the triangle is `y * width + x`, and y increases upwards. The triangle at `(x, y)` points up if
+/// `x + y` is even, and down otherwise; see [`points_up`](#method.points_up).
+///
+/// Neighbors are ordered counter-clockwise by the angles of the centroids of the triangles, measured from the
+/// positive x axis. A triangle that points up shares its edges with the triangles to its right, to its left,
+/// and below it; a triangle that points down shares its edges with the triangles above it, to its left, and to its right.
+///
+/// # Wrapping
+///
+/// Coordinates wrap around the limits of the grid. Wrapping keeps the orientation of each triangle consistent
+/// with its neighbors only if `width` and `height` are even, so [`new`](#method.new) rejects odd dimensions.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::WrappingTri2d;
+/// let tri = WrappingTri2d::new(10, 10).unwrap();
+/// // The triangle at (4, 4) points up:
+/// assert!(tri.points_up(44));
+/// assert_eq!(tri.neighbors3(44), vec![45, 43, 34]);
+/// // The triangle at (5, 4) points down:
+/// assert!(!tri.points_up(45));
+/// assert_eq!(tri.neighbors3(45), vec![55, 44, 46]);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct WrappingTri2d {
+    /// Width of the grid; it has to be larger than 0 and even.
+    w32: i32,
+    /// Height of the grid; it has to be larger than 0 and even.
+    h32: i32,
+    /// Total number of cells in the grid; it has to be larger than 0 and smaller than std::i32::MAX.
+    sz32: i32,
+    /// Width of the grid.
+    wu: usize,
+    /// Total number of cells in the grid.
+    szu: usize,
+}
+
+impl WrappingTri2d {
+    /// Constructs a new WrappingTri2d object.
+    ///
+    /// # Errors
+    ///
+    /// `width` and `height` must be larger than 0, and their product must be smaller than `std::i32::MAX`;
+    /// see [`WrappingCoords2d::new`]. Also, `width` and `height` must be even; otherwise, this function returns
+    /// `ErrorKind::DimensionsNotEven`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{ErrorKind, WrappingTri2d};
+    /// assert!(WrappingTri2d::new(10, 4).is_ok());
+    /// assert!(matches!(WrappingTri2d::new(9, 4), Err(ErrorKind::DimensionsNotEven)));
+    /// assert!(matches!(WrappingTri2d::new(10, 0), Err(ErrorKind::DimensionsLessThan1)));
+    /// ```
+    pub fn new(width: i32, height: i32) -> Result<WrappingTri2d, ErrorKind> {
+        let w2d = WrappingCoords2d::new(width, height)?;
+        if width % 2 != 0 || height % 2 != 0 {
+            return Err(ErrorKind::DimensionsNotEven);
+        }
+        Ok(WrappingTri2d {
+            w32: width,
+            h32: height,
+            sz32: w2d.size32(),
+            wu: width as usize,
+            szu: w2d.size(),
+        })
+    }
+    /// Returns the width of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 20).unwrap();
+    /// assert_eq!(tri.width(), 10);
+    /// ```
+    pub fn width(&self) -> i32 {
+        self.w32
+    }
+    /// Returns the height of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 20).unwrap();
+    /// assert_eq!(tri.height(), 20);
+    /// ```
+    pub fn height(&self) -> i32 {
+        self.h32
+    }
+    /// Returns the total number of cells in the grid. Use this to initialize 1D containers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 20).unwrap();
+    /// assert_eq!(tri.size(), 200 as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.szu
+    }
+    /// Returns the total number of cells in the grid as an `i32` number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 20).unwrap();
+    /// assert_eq!(tri.size32(), 200);
+    /// ```
+    pub fn size32(&self) -> i32 {
+        self.sz32
+    }
+    /// Returns an index into the grid based on `x` and `y` coordinates. Coordinates wrap around the limits
+    /// of the grid and can take any value in the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.index(5, 9), 95);
+    /// assert_eq!(tri.index(-1, -1), 99);
+    /// ```
+    pub fn index(&self, x: i32, y: i32) -> usize {
+        let mx = WrappingCoords2d::modulo(x, self.w32);
+        let my = WrappingCoords2d::modulo(y, self.h32);
+        (my * self.w32 + mx) as usize // Smaller than size; no overflow
+    }
+    /// Returns `x` and `y` coordinates based on an `index` into the 1D container.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index. Use [`coords_checked`](#method.coords_checked)
+    /// for indices that may lie outside the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.coords(95), (5, 9));
+    /// ```
+    pub fn coords(&self, index: usize) -> (i32, i32) {
+        let idx32 = index as i32; // Always positive
+        (idx32 % self.w32, idx32 / self.w32)
+    }
+    /// Returns `x` and `y` coordinates based on an `index` into the 1D container,
+    /// or `None` if `index` is not in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.coords_checked(99), Some((9, 9)));
+    /// assert_eq!(tri.coords_checked(100), None);
+    /// ```
+    pub fn coords_checked(&self, index: usize) -> Option<(i32, i32)> {
+        if index < self.szu {
+            Some(self.coords(index))
+        } else {
+            None
+        }
+    }
+    /// Returns `true` if the triangle given by `index` points up, that is, if the sum of its coordinates is even.
+    /// Every edge neighbor of a triangle has the opposite orientation.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `index` is a valid index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert!(tri.points_up(0));
+    /// assert!(!tri.points_up(1));
+    /// assert!(!tri.points_up(10));
+    /// assert!(tri.points_up(11));
+    /// ```
+    pub fn points_up(&self, index: usize) -> bool {
+        let (x, y) = self.coords(index);
+        (x + y) % 2 == 0
+    }
+    /// Returns a new index into the grid based on a starting index `start_index`, and x and y offsets.
+    /// Offsets can be negative and can take any value in the range of `i32`. The orientation of the new triangle
+    /// differs from the orientation of the starting triangle if `delta_x + delta_y` is odd.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.shift(0, -1, -1), 99);
+    /// assert_eq!(tri.shift(0, i32::MAX, i32::MIN), 27);
+    /// ```
+    pub fn shift(&self, start_index: usize, delta_x: i32, delta_y: i32) -> usize {
+        let (x, y) = self.coords(start_index % self.szu); // Reduced, so that invalid indices stay in the grid
+        let new_x = WrappingCoords2d::wrapping_add(x, delta_x, self.w32);
+        let new_y = WrappingCoords2d::wrapping_add(y, delta_y, self.h32);
+        (new_y * self.w32 + new_x) as usize
+    }
+    /// Returns the indices to the neighbors of the triangle given by `start_index`, given the offsets of the
+    /// neighborhood of the triangles that point up and down.
+    fn neighbors_array<const N: usize>(
+        &self,
+        start_index: usize,
+        up: &[(i32, i32); N],
+        down: &[(i32, i32); N],
+    ) -> [usize; N] {
+        let offsets = if self.points_up(start_index) {
+            up
+        } else {
+            down
+        };
+        offsets.map(|(dx, dy)| self.shift(start_index, dx, dy))
+    }
+    /// This function takes the triangle given by `start_index` and returns a vector of the indices to its 3 edge neighbors,
+    /// ordered counter-clockwise. For a triangle that points up, the neighbors are to its right, to its left, and below it;
+    /// for a triangle that points down, the neighbors are above it, to its left, and to its right.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.neighbors3(0), vec![1, 9, 90]);
+    /// assert_eq!(tri.neighbors3(9), vec![19, 8, 0]);
+    /// ```
+    pub fn neighbors3(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors3_array(start_index).to_vec()
+    }
+    /// This function takes the triangle given by `start_index` and returns an array of the indices to its 3 edge neighbors,
+    /// in the same order as [`neighbors3`](#method.neighbors3). Unlike `neighbors3`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// assert_eq!(tri.neighbors3_array(0), [1, 9, 90]);
+    /// ```
+    pub fn neighbors3_array(&self, start_index: usize) -> [usize; 3] {
+        self.neighbors_array(start_index, &UP_OFFSETS3, &DOWN_OFFSETS3)
+    }
+    /// This function takes the triangle given by `start_index` and returns a vector of the indices to its 12 vertex neighbors,
+    /// which share at least one vertex with the triangle. The indices are ordered counter-clockwise, starting from the
+    /// second triangle to the right.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// // The triangle at (4, 4) points up:
+    /// assert_eq!(tri.neighbors12(44), vec![46, 45, 55, 54, 53, 43, 42, 32, 33, 34, 35, 36]);
+    /// ```
+    pub fn neighbors12(&self, start_index: usize) -> std::vec::Vec<usize> {
+        self.neighbors12_array(start_index).to_vec()
+    }
+    /// This function takes the triangle given by `start_index` and returns an array of the indices to its 12 vertex neighbors,
+    /// in the same order as [`neighbors12`](#method.neighbors12). Unlike `neighbors12`, this function does not allocate memory.
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `start_index` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// // The triangle at (5, 4) points down:
+    /// assert_eq!(tri.neighbors12_array(45), [47, 57, 56, 55, 54, 53, 43, 44, 34, 35, 36, 46]);
+    /// ```
+    pub fn neighbors12_array(&self, start_index: usize) -> [usize; 12] {
+        self.neighbors_array(start_index, &UP_OFFSETS12, &DOWN_OFFSETS12)
+    }
+    /// Calls a closure `f` on each triangle of the grid. Each call acts on the triangle and the neighbors given by
+    /// `up` or `down`, depending on its orientation. Each row rebuilds its shifts for its even and odd columns, with
+    /// x shifts in the form (x + w) and y shifts reduced to yw, so the inner loop only adds to and reduces usize values.
+    fn for_each<F, const N: usize>(&self, mut f: F, up: &[(i32, i32); N], down: &[(i32, i32); N])
+    where
+        F: FnMut(usize, &[usize; N]),
+    {
+        let mut neighbors = [0; N];
+        let mut this_cell_index = 0;
+        for y in 0..self.h32 {
+            // Offsets of the triangles in even and odd columns of this row
+            let tables = if y % 2 == 0 { [up, down] } else { [down, up] };
+            let x_shifts = tables.map(|offsets| {
+                offsets.map(|(dx, _)| WrappingCoords2d::modulo(dx, self.w32) as usize + self.wu)
+            });
+            let yw_shifts = tables.map(|offsets| {
+                offsets.map(|(_, dy)| WrappingCoords2d::modulo(y + dy, self.h32) as usize * self.wu)
+            });
+            for x in 0..self.wu {
+                let parity = x % 2;
+                for (j, neighbor) in neighbors.iter_mut().enumerate() {
+                    *neighbor = yw_shifts[parity][j] + (x_shifts[parity][j] + x) % self.wu;
+                }
+                f(this_cell_index, &neighbors);
+                this_cell_index += 1;
+            }
+        }
+    }
+    /// Calls a closure `f` on each triangle of the grid. Each call acts on the triangle and its 3 edge neighbors,
+    /// ordered as in [`neighbors3`](#method.neighbors3).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// tri.for_each3(|this_cell_index, neighbors| {
+    ///     for &neighbor_index in neighbors {
+    ///         assert_ne!(tri.points_up(this_cell_index), tri.points_up(neighbor_index));
+    ///     }
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, tri.size());
+    /// ```
+    pub fn for_each3<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 3]),
+    {
+        self.for_each(f, &UP_OFFSETS3, &DOWN_OFFSETS3)
+    }
+    /// Calls a closure `f` on each triangle of the grid. Each call acts on the triangle and its 12 vertex neighbors,
+    /// ordered as in [`neighbors12`](#method.neighbors12).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// tri.for_each12(|this_cell_index, neighbors| {
+    ///     assert_eq!(neighbors[0], tri.shift(this_cell_index, 2, 0));
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, tri.size());
+    /// ```
+    pub fn for_each12<F>(&self, f: F)
+    where
+        F: FnMut(usize, &[usize; 12]),
+    {
+        self.for_each(f, &UP_OFFSETS12, &DOWN_OFFSETS12)
+    }
+    /// Calls a closure `f` on each triangle of the grid. Each call acts on the triangle and one of its 3 edge neighbors,
+    /// ordered as in [`neighbors3`](#method.neighbors3).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// tri.for_each_pair3(|this_cell_index, neighbor_index| {
+    ///     assert!(this_cell_index != neighbor_index);
+    ///     calls_counter += 1;
+    /// });
+    /// assert_eq!(calls_counter, 3 * tri.size());
+    /// ```
+    pub fn for_each_pair3<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each3(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+    /// Calls a closure `f` on each triangle of the grid. Each call acts on the triangle and one of its 12 vertex neighbors,
+    /// ordered as in [`neighbors12`](#method.neighbors12).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::WrappingTri2d;
+    /// let tri = WrappingTri2d::new(10, 10).unwrap();
+    /// let mut calls_counter = 0;
+    /// tri.for_each_pair12(|_, _| calls_counter += 1);
+    /// assert_eq!(calls_counter, 12 * tri.size());
+    /// ```
+    pub fn for_each_pair12<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.for_each12(|this_cell_index, neighbors| {
+            for &neighbor_index in neighbors {
+                f(this_cell_index, neighbor_index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the vertices of the triangle at `(x, y)`, in units of half a side horizontally and one row vertically.
+    fn vertices(x: i32, y: i32) -> [(i32, i32); 3] {
+        if (x + y).rem_euclid(2) == 0 {
+            [(x - 1, y), (x + 1, y), (x, y + 1)]
+        } else {
+            [(x - 1, y + 1), (x + 1, y + 1), (x, y)]
+        }
+    }
+
+    /// Returns the angle of the centroid of the triangle at `(x + dx, y + dy)`, seen from the centroid
+    /// of the triangle at `(x, y)`, in the range [0, 2 pi).
+    fn angle(x: i32, y: i32, dx: i32, dy: i32) -> f64 {
+        let s = 3f64.sqrt() / 2.0;
+        let centroid = |x: i32, y: i32| {
+            let up = (x + y).rem_euclid(2) == 0;
+            (
+                x as f64 / 2.0,
+                y as f64 * s + if up { s / 3.0 } else { 2.0 * s / 3.0 },
+            )
+        };
+        let (x0, y0) = centroid(x, y);
+        let (x1, y1) = centroid(x + dx, y + dy);
+        (y1 - y0)
+            .atan2(x1 - x0)
+            .rem_euclid(2.0 * std::f64::consts::PI)
+    }
+
+    #[test]
+    fn triangular_grids() {
+        assert!(matches!(
+            WrappingTri2d::new(4, 3),
+            Err(ErrorKind::DimensionsNotEven)
+        ));
+        assert!(matches!(
+            WrappingTri2d::new(3, 4),
+            Err(ErrorKind::DimensionsNotEven)
+        ));
+        assert!(matches!(
+            WrappingTri2d::new(46342, 46342),
+            Err(ErrorKind::DimensionsTooLarge)
+        ));
+        // The offset tables match the geometry of the lattice
+        for &(x, y) in [(0, 0), (1, 0)].iter() {
+            let (offsets3, offsets12) = if (x + y) % 2 == 0 {
+                (UP_OFFSETS3, UP_OFFSETS12)
+            } else {
+                (DOWN_OFFSETS3, DOWN_OFFSETS12)
+            };
+            let shared = |dx: i32, dy: i32| {
+                let other = vertices(x + dx, y + dy);
+                vertices(x, y).iter().filter(|v| other.contains(v)).count()
+            };
+            let mut edge = std::vec::Vec::new();
+            let mut vertex = std::vec::Vec::new();
+            for dy in -3..=3 {
+                for dx in -3..=3 {
+                    match ((dx, dy), shared(dx, dy)) {
+                        ((0, 0), _) => {}
+                        (offset, 2) => edge.push(offset),
+                        (offset, 1) => vertex.push(offset),
+                        _ => {}
+                    }
+                }
+            }
+            vertex.extend(edge.iter());
+            for offsets in [&offsets3[..], &offsets12[..]].iter() {
+                let angles: std::vec::Vec<f64> = offsets
+                    .iter()
+                    .map(|&(dx, dy)| angle(x, y, dx, dy))
+                    .collect();
+                assert!(angles.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+            edge.sort_unstable();
+            vertex.sort_unstable();
+            let mut sorted3 = offsets3.to_vec();
+            let mut sorted12 = offsets12.to_vec();
+            sorted3.sort_unstable();
+            sorted12.sort_unstable();
+            assert_eq!(sorted3, edge);
+            assert_eq!(sorted12, vertex);
+        }
+        for &(w, h) in [(10, 10), (6, 4), (2, 2), (4, 8)].iter() {
+            let tri = WrappingTri2d::new(w, h).unwrap();
+            for index in 0..tri.size() {
+                let (x, y) = tri.coords(index);
+                assert_eq!(tri.index(x - w, y + 3 * h), index);
+                assert_eq!(
+                    tri.shift(index, i32::MAX, i32::MIN),
+                    tri.index(x + i32::MAX % w, y + i32::MIN % h)
+                );
+                // Edge adjacency is symmetric and flips the orientation
+                for &neighbor_index in tri.neighbors3_array(index).iter() {
+                    assert_ne!(tri.points_up(index), tri.points_up(neighbor_index));
+                    assert!(tri.neighbors3(neighbor_index).contains(&index));
+                }
+                for &neighbor_index in tri.neighbors12_array(index).iter() {
+                    assert!(tri.neighbors12(neighbor_index).contains(&index));
+                }
+            }
+            let mut calls_counter = 0;
+            tri.for_each3(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &tri.neighbors3_array(this_cell_index));
+                calls_counter += 1;
+            });
+            tri.for_each12(|this_cell_index, neighbors| {
+                assert_eq!(neighbors, &tri.neighbors12_array(this_cell_index));
+                calls_counter += 1;
+            });
+            assert_eq!(calls_counter, 2 * tri.size());
+        }
+        // Invalid start indices wrap around the grid
+        let tri = WrappingTri2d::new(10, 10).unwrap();
+        assert_eq!(tri.shift(250, 0, 0), 50);
+        assert_eq!(tri.shift(250, 3, -7), tri.shift(50, 3, -7));
+    }
+}
@@ -0,0 +1,337 @@
+/* WrappingCoords2d: Rust crate to translate between 1D indices and 2D coordinates with wrapping https://crates.io/crates/wrapping_coords2d
+
+    Copyright © 2020-2022 Fabio A. Correa Duran facorread@gmail.com
+
+   Licensed under the Apache License, Version 2.0 (the "License");
+   you may not use this file except in compliance with the License.
+   You may obtain a copy of the License at
+
+       http://www.apache.org/licenses/LICENSE-2.0
+
+   Unless required by applicable law or agreed to in writing, software
+   distributed under the License is distributed on an "AS IS" BASIS,
+   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+   See the License for the specific language governing permissions and
+   limitations under the License.
+*/
+
+use crate::WrappingCoords2d;
+
+/// Represents an index into the 1D container of a grid.
+///
+/// `CellIndex` keeps cell indices apart from other `usize` numbers, such as agent ids, so that passing one where
+/// the other is expected is a compile error. The typed functions of [`WrappingCoords2d`], such as
+/// [`shift_typed`](WrappingCoords2d::shift_typed), take and return `CellIndex` values; the raw functions keep
+/// working with `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{CellIndex, WrappingCoords2d};
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// let cell = CellIndex(95);
+/// let mut heights = vec![0.0; w2d.size()];
+/// heights[usize::from(cell)] = 1.5;
+/// assert_eq!(heights[cell.0], 1.5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellIndex(pub usize);
+
+impl From<usize> for CellIndex {
+    fn from(index: usize) -> Self {
+        CellIndex(index)
+    }
+}
+
+impl From<CellIndex> for usize {
+    fn from(cell: CellIndex) -> Self {
+        cell.0
+    }
+}
+
+/// Represents the coordinates of a cell, or an offset between two cells.
+///
+/// `Coord` values add and subtract componentwise, so that a cell plus an offset is another cell. Like the raw
+/// functions of [`WrappingCoords2d`], the typed functions wrap coordinates around the limits of the grid.
+///
+/// # Panics
+///
+/// The operators `+`, `-`, and unary `-` follow the arithmetic of `i32`: they panic on overflow in debug builds.
+/// To move a cell near the limits of `i32`, pass the offset to [`shift_typed`](WrappingCoords2d::shift_typed)
+/// instead, which never overflows.
+///
+/// # Examples
+///
+/// ```
+/// use wrapping_coords2d::{CellIndex, Coord, WrappingCoords2d};
+/// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+/// let north_east = Coord::new(1, 1);
+/// let here = Coord::new(9, 9);
+/// assert_eq!(here + north_east, Coord::new(10, 10));
+/// assert_eq!(w2d.index_typed(here + north_east), w2d.index_typed(Coord::new(0, 0)));
+/// assert_eq!(here - north_east, Coord::from((8, 8)));
+/// assert_eq!(<(i32, i32)>::from(-north_east), (-1, -1));
+/// // Coord::new(i32::MAX, 0) + Coord::new(1, 0) overflows, but shift_typed wraps around the grid:
+/// let far_right = w2d.index_typed(Coord::new(i32::MAX, 0));
+/// assert_eq!(w2d.shift_typed(far_right, Coord::new(1, 0)), CellIndex(8));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Coord {
+    /// x coordinate, increasing to the right.
+    pub x: i32,
+    /// y coordinate, increasing upwards.
+    pub y: i32,
+}
+
+impl Coord {
+    /// Constructs a new Coord object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::Coord;
+    /// assert_eq!(Coord::new(3, 4), Coord { x: 3, y: 4 });
+    /// ```
+    pub fn new(x: i32, y: i32) -> Coord {
+        Coord { x, y }
+    }
+}
+
+impl From<(i32, i32)> for Coord {
+    fn from((x, y): (i32, i32)) -> Self {
+        Coord { x, y }
+    }
+}
+
+impl From<Coord> for (i32, i32) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+impl std::ops::Add for Coord {
+    type Output = Coord;
+    fn add(self, rhs: Coord) -> Coord {
+        Coord::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl std::ops::Sub for Coord {
+    type Output = Coord;
+    fn sub(self, rhs: Coord) -> Coord {
+        Coord::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl std::ops::Neg for Coord {
+    type Output = Coord;
+    fn neg(self) -> Coord {
+        Coord::new(-self.x, -self.y)
+    }
+}
+
+impl std::ops::AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Coord) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::SubAssign for Coord {
+    fn sub_assign(&mut self, rhs: Coord) {
+        *self = *self - rhs;
+    }
+}
+
+/// Typed variants of the functions of `WrappingCoords2d`, using [`CellIndex`] and [`Coord`].
+impl WrappingCoords2d {
+    /// Returns the index of the cell at `coord`; see [`index`](#method.index).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `coord` lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, Coord, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.index_typed(Coord::new(5, 9)), CellIndex(95));
+    /// assert_eq!(w2d.index_typed(Coord::new(-1, -1)), CellIndex(99));
+    /// ```
+    pub fn index_typed(&self, coord: Coord) -> CellIndex {
+        CellIndex(self.index(coord.x, coord.y))
+    }
+    /// Returns the coordinates of the cell given by `cell`; see [`coords`](#method.coords).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, Coord, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.coords_typed(CellIndex(95)), Coord::new(5, 9));
+    /// ```
+    pub fn coords_typed(&self, cell: CellIndex) -> Coord {
+        self.coords(cell.0).into()
+    }
+    /// Returns the cell at `offset` from the cell given by `cell`; see [`shift`](#method.shift).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index. However, it returns a valid index in the range [0, size).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the new cell lies beyond an edge with `Boundary::Absorb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, Coord, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.shift_typed(CellIndex(95), Coord::new(1, 1)), CellIndex(6));
+    /// ```
+    pub fn shift_typed(&self, cell: CellIndex, offset: Coord) -> CellIndex {
+        CellIndex(self.shift(cell.0, offset.x, offset.y))
+    }
+    /// Returns the 4 neighbors of the cell given by `cell`, in the order of [`neighbors4`](#method.neighbors4).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(
+    ///     w2d.neighbors4_typed(CellIndex(0)),
+    ///     vec![CellIndex(1), CellIndex(10), CellIndex(9), CellIndex(90)]
+    /// );
+    /// ```
+    pub fn neighbors4_typed(&self, cell: CellIndex) -> std::vec::Vec<CellIndex> {
+        self.neighbors4(cell.0).into_iter().map(CellIndex).collect()
+    }
+    /// Returns the 8 neighbors of the cell given by `cell`, in the order of [`neighbors8`](#method.neighbors8).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors8_typed(CellIndex(0))[1], CellIndex(11));
+    /// ```
+    pub fn neighbors8_typed(&self, cell: CellIndex) -> std::vec::Vec<CellIndex> {
+        self.neighbors8(cell.0).into_iter().map(CellIndex).collect()
+    }
+    /// Returns the 16 second neighbors of the cell given by `cell`, in the order of [`neighbors16`](#method.neighbors16).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors16_typed(CellIndex(55))[0], CellIndex(57));
+    /// ```
+    pub fn neighbors16_typed(&self, cell: CellIndex) -> std::vec::Vec<CellIndex> {
+        self.neighbors16(cell.0)
+            .into_iter()
+            .map(CellIndex)
+            .collect()
+    }
+    /// Returns the 24 neighbors of the cell given by `cell`, in the order of [`neighbors24`](#method.neighbors24).
+    ///
+    /// # Safety
+    ///
+    /// This function does not check that `cell` is a valid index. However, it returns valid indices in the range [0, size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wrapping_coords2d::{CellIndex, WrappingCoords2d};
+    /// let w2d = WrappingCoords2d::new(10, 10).unwrap();
+    /// assert_eq!(w2d.neighbors24_typed(CellIndex(55))[8], CellIndex(57));
+    /// ```
+    pub fn neighbors24_typed(&self, cell: CellIndex) -> std::vec::Vec<CellIndex> {
+        self.neighbors24(cell.0)
+            .into_iter()
+            .map(CellIndex)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Boundary;
+
+    #[test]
+    fn typed_api() {
+        let grids = [
+            WrappingCoords2d::new(10, 10).unwrap(),
+            WrappingCoords2d::new(3, 7).unwrap(),
+            WrappingCoords2d::new_with_boundaries(5, 4, Boundary::Wrap, Boundary::Absorb).unwrap(),
+        ];
+        for w2d in grids.iter() {
+            for index in 0..w2d.size() {
+                let cell = CellIndex::from(index);
+                let coord = w2d.coords_typed(cell);
+                assert_eq!(<(i32, i32)>::from(coord), w2d.coords(index));
+                assert_eq!(w2d.index_typed(coord), cell);
+                let offset = Coord::new(2, -1);
+                assert_eq!(
+                    w2d.shift_checked(index, 2, -1).map(CellIndex),
+                    w2d.index_checked(coord.x + offset.x, coord.y + offset.y)
+                        .map(CellIndex)
+                );
+                if let Some(target) = w2d.shift_checked(index, 2, -1) {
+                    assert_eq!(w2d.shift_typed(cell, offset), CellIndex(target));
+                    assert_eq!(w2d.index_typed(coord + offset), CellIndex(target));
+                }
+                let raw = [
+                    w2d.neighbors4(index),
+                    w2d.neighbors8(index),
+                    w2d.neighbors16(index),
+                    w2d.neighbors24(index),
+                ];
+                let typed = [
+                    w2d.neighbors4_typed(cell),
+                    w2d.neighbors8_typed(cell),
+                    w2d.neighbors16_typed(cell),
+                    w2d.neighbors24_typed(cell),
+                ];
+                for (raw, typed) in raw.iter().zip(typed.iter()) {
+                    let typed: std::vec::Vec<usize> =
+                        typed.iter().map(|&c| usize::from(c)).collect();
+                    assert_eq!(raw, &typed);
+                }
+            }
+        }
+        let mut offset = Coord::new(1, 2);
+        offset += Coord::new(3, 4);
+        assert_eq!(offset, Coord::new(4, 6));
+        offset -= Coord::new(4, 6);
+        assert_eq!(offset, Coord::default());
+        assert!(CellIndex(3) < CellIndex(4));
+        // Offsets at the limits of i32 move through shift_typed without overflow
+        let w2d = WrappingCoords2d::new(10, 10).unwrap();
+        let corner = w2d.index_typed(Coord::new(i32::MAX, i32::MIN));
+        let offset = Coord::new(i32::MAX, i32::MIN);
+        assert_eq!(
+            w2d.shift_typed(corner, offset),
+            CellIndex(w2d.shift(corner.0, i32::MAX, i32::MIN))
+        );
+    }
+}